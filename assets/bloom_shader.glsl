@@ -0,0 +1,54 @@
+// Single pass bloom: bright pixels bleed into their neighbours.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct PostUniforms {
+    screen_width: f32,
+    screen_height: f32,
+    intensity: f32,
+    curvature: f32,
+    crt_lines_x: f32,
+    crt_lines_y: f32,
+    scanlines: f32,
+    scanline_opacity: f32,
+    bloom_threshold: f32,
+    bloom_radius: f32,
+    bloom_strength: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+@group(3) @binding(0)
+var<uniform> post: PostUniforms;
+
+fn bright(color: vec4<f32>) -> vec3<f32> {
+  var luma: f32 = dot(color.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+  return color.rgb * max(luma - post.bloom_threshold, 0.0) / max(1.0 - post.bloom_threshold, 0.001);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  var color: vec4<f32> = textureSample(t, s, in.uv) * in.color;
+  var texel: vec2<f32> = vec2<f32>(post.bloom_radius / post.screen_width, post.bloom_radius / post.screen_height);
+  var glow: vec3<f32> = vec3<f32>(0.0);
+  var total: f32 = 0.0;
+
+  for (var x: i32 = -2; x <= 2; x = x + 1) {
+    for (var y: i32 = -2; y <= 2; y = y + 1) {
+      var weight: f32 = 1.0 / (1.0 + f32(x * x + y * y));
+      var offset: vec2<f32> = vec2<f32>(f32(x), f32(y)) * texel;
+      glow = glow + bright(textureSample(t, s, in.uv + offset)) * weight;
+      total = total + weight;
+    }
+  }
+
+  return vec4<f32>(color.rgb + glow / total * post.bloom_strength * post.intensity, color.a);
+}
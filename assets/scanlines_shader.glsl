@@ -0,0 +1,41 @@
+// Horizontal scanlines without the CRT curvature.
+
+let PI: f32 = 3.14159;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct PostUniforms {
+    screen_width: f32,
+    screen_height: f32,
+    intensity: f32,
+    curvature: f32,
+    crt_lines_x: f32,
+    crt_lines_y: f32,
+    scanlines: f32,
+    scanline_opacity: f32,
+    bloom_threshold: f32,
+    bloom_radius: f32,
+    bloom_strength: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+@group(3) @binding(0)
+var<uniform> post: PostUniforms;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  var color: vec4<f32> = textureSample(t, s, in.uv) * in.color;
+  var wave: f32 = sin(in.uv.y * post.scanlines * PI * 2.0) * 0.5 + 0.5;
+  var shade: f32 = pow(wave * 0.9 + 0.1, post.scanline_opacity);
+
+  return vec4<f32>(color.rgb * mix(1.0, shade, post.intensity), color.a);
+}
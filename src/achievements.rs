@@ -0,0 +1,284 @@
+use serde::Deserialize;
+
+use crate::assets::AssetSource;
+use crate::core::DuelOutcome;
+use crate::error::AssetError;
+use crate::events::{GameEvent, Subscriber};
+use crate::profile::{MatchSummary, Profile};
+
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComboKind {
+    Same,
+    Plus,
+    Combo,
+}
+
+/// What a match must show to count towards an achievement. Everything but
+/// `chain` and `trigger` also needs the player to win.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    Win,
+    /// Win with at least this many cards, 9 is a 9-1 win.
+    Score {
+        score: u8,
+    },
+    /// Capture this many cards with a single move, combos included.
+    Chain {
+        cards: u32,
+    },
+    /// Trigger the combo this many times in one match.
+    Trigger {
+        combo: ComboKind,
+        count: u32,
+    },
+    /// Win with no card above this level.
+    MaxLevel {
+        level: u8,
+    },
+    /// Win after at least this many Sudden Death rounds.
+    SuddenDeath {
+        rounds: u32,
+    },
+    /// Win against an AI of at least this difficulty.
+    Difficulty {
+        difficulty: usize,
+    },
+}
+
+impl Condition {
+    fn met(&self, summary: &MatchSummary) -> bool {
+        let record = &summary.record;
+        let won = matches!(record.outcome, DuelOutcome::Win);
+        match self {
+            Self::Win => won,
+            Self::Score { score } => won && record.score[0] >= *score,
+            Self::Chain { cards } => summary.best_chain >= *cards,
+            Self::Trigger { combo, count } => {
+                let triggered = match combo {
+                    ComboKind::Same => summary.same,
+                    ComboKind::Plus => summary.plus,
+                    ComboKind::Combo => summary.combo,
+                };
+                triggered >= *count
+            }
+            Self::MaxLevel { level } => won && summary.levels.iter().all(|l| l <= level),
+            Self::SuddenDeath { rounds } => won && record.sudden_death_rounds >= *rounds,
+            Self::Difficulty { difficulty } => won && record.difficulty >= *difficulty,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: Condition,
+    /// Matches meeting the condition needed to unlock it.
+    #[serde(default = "one")]
+    pub times: u32,
+}
+
+const fn one() -> u32 {
+    1
+}
+
+/// The achievements listed in `achievements.json`.
+#[derive(Deserialize, Debug, Default)]
+pub struct Achievements {
+    pub achievements: Vec<Achievement>,
+    /// The achievements recorded matches met the condition of, until they
+    /// are counted towards a profile.
+    #[serde(skip)]
+    met: Vec<usize>,
+}
+
+impl Achievements {
+    pub fn load(assets: &AssetSource) -> Result<Self, AssetError> {
+        let achievements: Self = assets.json(ACHIEVEMENTS_FILE)?;
+        for (n, achievement) in achievements.achievements.iter().enumerate() {
+            if achievements.achievements[..n]
+                .iter()
+                .any(|other| other.id == achievement.id)
+            {
+                return Err(AssetError::Invalid {
+                    file: ACHIEVEMENTS_FILE.into(),
+                    message: format!("{}: the id is used twice", achievement.id),
+                });
+            }
+        }
+        Ok(achievements)
+    }
+
+    /// Counts the matches recorded since the last call towards the locked
+    /// achievements whose condition they met and returns the ones unlocked.
+    pub fn unlock(&mut self, profile: &mut Profile) -> Vec<&Achievement> {
+        let mut unlocked = Vec::new();
+        for n in std::mem::take(&mut self.met) {
+            let achievement = &self.achievements[n];
+            if profile.unlocked.contains(&achievement.id) {
+                continue;
+            }
+            let progress = profile
+                .achievements
+                .entry(achievement.id.clone())
+                .or_default();
+            *progress += 1;
+            if *progress >= achievement.times {
+                profile.unlocked.push(achievement.id.clone());
+                unlocked.push(achievement);
+            }
+        }
+        unlocked
+    }
+}
+
+impl Subscriber for Achievements {
+    fn notify(&mut self, event: &GameEvent) {
+        if let GameEvent::MatchRecorded(summary) = event {
+            let met = self
+                .achievements
+                .iter()
+                .enumerate()
+                .filter(|(_, achievement)| achievement.condition.met(summary))
+                .map(|(n, _)| n);
+            self.met.extend(met);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::MatchRecord;
+
+    /// A 6-4 win in a single round against a level 2 AI, with level 1 to 5
+    /// cards, one Same, two Combos and a best chain of 3 cards.
+    fn summary() -> MatchSummary {
+        MatchSummary {
+            record: MatchRecord {
+                outcome: DuelOutcome::Win,
+                opponent: "Random".into(),
+                difficulty: 2,
+                rules: "Open".into(),
+                score: [6, 4],
+                sudden_death_rounds: 0,
+                cards: Vec::new(),
+                moves: Vec::new(),
+            },
+            same: 1,
+            plus: 0,
+            combo: 2,
+            best_chain: 3,
+            levels: vec![1, 2, 3, 4, 5],
+            faced: Vec::new(),
+            captured: Vec::new(),
+        }
+    }
+
+    fn lost() -> MatchSummary {
+        let mut summary = summary();
+        summary.record.outcome = DuelOutcome::Lose;
+        summary.record.score = [4, 6];
+        summary
+    }
+
+    #[test]
+    fn conditions() {
+        let trigger = |combo, count| Condition::Trigger { combo, count };
+        let table = [
+            (Condition::Win, true, false),
+            (Condition::Score { score: 6 }, true, false),
+            (Condition::Score { score: 7 }, false, false),
+            (Condition::Chain { cards: 3 }, true, true),
+            (Condition::Chain { cards: 4 }, false, false),
+            (trigger(ComboKind::Same, 1), true, true),
+            (trigger(ComboKind::Plus, 1), false, false),
+            (trigger(ComboKind::Combo, 2), true, true),
+            (trigger(ComboKind::Combo, 3), false, false),
+            (Condition::MaxLevel { level: 5 }, true, false),
+            (Condition::MaxLevel { level: 4 }, false, false),
+            (Condition::SuddenDeath { rounds: 0 }, true, false),
+            (Condition::SuddenDeath { rounds: 1 }, false, false),
+            (Condition::Difficulty { difficulty: 2 }, true, false),
+            (Condition::Difficulty { difficulty: 3 }, false, false),
+        ];
+        for (condition, won, lost_too) in table {
+            assert_eq!(condition.met(&summary()), won, "{condition:?} won");
+            assert_eq!(condition.met(&lost()), lost_too, "{condition:?} lost");
+        }
+    }
+
+    #[test]
+    fn sudden_death_rounds_count() {
+        let mut summary = summary();
+        summary.record.sudden_death_rounds = 2;
+        assert!(Condition::SuddenDeath { rounds: 2 }.met(&summary));
+        assert!(!Condition::SuddenDeath { rounds: 3 }.met(&summary));
+    }
+
+    fn achievement(id: &str, condition: Condition, times: u32) -> Achievement {
+        Achievement {
+            id: id.into(),
+            name: id.into(),
+            description: String::new(),
+            condition,
+            times,
+        }
+    }
+
+    fn unlocked(
+        achievements: &mut Achievements,
+        summary: &MatchSummary,
+        profile: &mut Profile,
+    ) -> Vec<String> {
+        achievements.notify(&GameEvent::MatchRecorded(Box::new(summary.clone())));
+        achievements
+            .unlock(profile)
+            .into_iter()
+            .map(|achievement| achievement.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn progress_unlocks_after_enough_matches() {
+        let mut achievements = Achievements {
+            achievements: vec![
+                achievement("first", Condition::Win, 1),
+                achievement("third", Condition::Win, 3),
+                achievement("perfect", Condition::Score { score: 9 }, 1),
+            ],
+            met: Vec::new(),
+        };
+        let mut profile = Profile::new("Test");
+
+        assert_eq!(
+            unlocked(&mut achievements, &summary(), &mut profile),
+            ["first"]
+        );
+        assert!(unlocked(&mut achievements, &lost(), &mut profile).is_empty());
+        assert!(unlocked(&mut achievements, &summary(), &mut profile).is_empty());
+        assert_eq!(profile.achievements["third"], 2);
+        assert!(!profile.achievements.contains_key("perfect"));
+
+        assert_eq!(
+            unlocked(&mut achievements, &summary(), &mut profile),
+            ["third"]
+        );
+        assert_eq!(profile.unlocked, ["first", "third"]);
+
+        // Unlocked achievements stop counting.
+        assert!(unlocked(&mut achievements, &summary(), &mut profile).is_empty());
+        assert_eq!(profile.achievements["first"], 1);
+        assert_eq!(profile.achievements["third"], 3);
+    }
+
+    #[test]
+    fn shipped_achievements_load() {
+        Achievements::load(&AssetSource::Embedded).unwrap();
+    }
+}
@@ -1,19 +1,27 @@
 use ggez::{
-    audio::{SoundSource, Source},
-    graphics::{Canvas, Image, InstanceArray},
+    graphics::{Canvas, Color, DrawParam, Image, InstanceArray, Text},
+    input::keyboard::KeyCode,
     Context, GameResult,
 };
 
 use std::rc::Rc;
 
 use crate::{
+    achievements::Achievements,
+    audio::AudioManager,
+    campaign::Campaign,
+    config::Config,
     core::CardAtlas,
+    events::{EventBus, Subscriber},
     graphics::sprite::Atlas,
+    opponents::Roster,
+    state::scene::{Hook, SceneStack},
     state::{Fade as FadeState, PlayState},
     utils::Sfx,
 };
 use crate::{graphics::Shape, utils::Event};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum State {
     FadeIn,
     FadeOut,
@@ -52,26 +60,22 @@ impl WgpuShapes {
     }
 }
 
-pub struct SoundManager {
-    pub bgm: Source,
-    pub flip_card: Source,
-    pub move_card: Source,
-    pub select: Source,
-    pub cancel: Source,
-    pub victory: Source,
-}
-
 pub struct App {
     play_state: PlayState,
     fade_state: FadeState,
-    state_stack: Vec<State>,
+    state_stack: SceneStack<State>,
+    /// Lists the active screens over the game, toggled with F3.
+    debug_overlay: bool,
     array: InstanceArray,
     elem_array: InstanceArray,
     pub wgpu_shapes: WgpuShapes,
-    sound_manager: SoundManager,
+    audio: AudioManager,
+    /// What the scene on top asked for during the frame.
+    events: EventBus<Event>,
 }
 
 impl App {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ctx: &mut Context,
         card_atlas: &Rc<CardAtlas>,
@@ -79,26 +83,73 @@ impl App {
         bg_image: &Rc<Image>,
         array: InstanceArray,
         elem_array: InstanceArray,
-        mut sound_manager: SoundManager,
+        audio: AudioManager,
+        campaign: Campaign,
+        roster: Roster,
+        achievements: Achievements,
     ) -> Self {
-        let play_state = PlayState::new(ctx, card_atlas, sprite_sheet, bg_image);
+        let play_state = PlayState::new(
+            ctx,
+            card_atlas,
+            sprite_sheet,
+            bg_image,
+            campaign,
+            roster,
+            achievements,
+        );
         let fade_state = FadeState::new(ctx);
-        sound_manager.bgm.set_volume(0.0);
-        sound_manager.victory.set_volume(0.0);
-        sound_manager.bgm.set_repeat(true);
-        Self {
+        let mut app = Self {
             play_state,
             fade_state,
-            state_stack: vec![State::Play, State::InitPlay, State::FadeIn],
+            state_stack: SceneStack::new(&[State::Play, State::InitPlay, State::FadeIn]),
+            debug_overlay: false,
             array,
             elem_array,
             wgpu_shapes: WgpuShapes::new(ctx),
-            sound_manager,
+            audio,
+            events: EventBus::default(),
+        };
+        app.apply_settings();
+        app.run_hooks();
+        app
+    }
+
+    /// Fades to black, runs `hidden` while the screen is black, then fades
+    /// back in and runs `after`.
+    fn transition(&mut self, hidden: State, after: State) {
+        self.state_stack
+            .push_all(&[after, State::FadeIn, hidden, State::FadeOut]);
+    }
+
+    fn run_hooks(&mut self) {
+        for hook in self.state_stack.take_hooks() {
+            match hook {
+                Hook::Enter(State::FadeIn) => self.fade_state.fade_in(),
+                Hook::Enter(State::FadeOut) => self.fade_state.fade_out(),
+                _ => {}
+            }
+        }
+    }
+
+    pub const fn config(&self) -> &Config {
+        self.play_state.config()
+    }
+
+    fn apply_settings(&mut self) {
+        let config = self.play_state.config();
+        self.fade_state.set_duration(config.gameplay.fade_duration);
+        self.audio
+            .apply_settings(&config.audio, config.gameplay.fade_duration);
+    }
+
+    pub fn text_input(&mut self, character: char) {
+        if let Some(State::Play) = self.state_stack.last() {
+            self.play_state.text_input(character);
         }
     }
 
     pub fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
-        for state in &self.state_stack {
+        for state in self.state_stack.iter() {
             match state {
                 State::InitPlay | State::ClearPlay => {}
                 State::Play => {
@@ -108,96 +159,78 @@ impl App {
                 State::FadeIn | State::FadeOut => self.fade_state.draw(canvas),
             }
         }
+
+        if self.debug_overlay {
+            let text = Text::new(format!(
+                "{}--\n{}",
+                self.state_stack.describe(),
+                self.play_state.describe()
+            ));
+            canvas.draw(
+                &text,
+                DrawParam::default()
+                    .dest([10.0, 10.0])
+                    .color(Color::from_rgb(255, 255, 0)),
+            );
+        }
     }
     pub fn update(&mut self, ctx: &mut Context) -> GameResult {
         let dt = ctx.time.delta().as_secs_f32();
 
+        if ctx.keyboard.is_key_just_pressed(KeyCode::F3) {
+            self.debug_overlay = !self.debug_overlay;
+        }
+
         self.wgpu_shapes.update(ctx);
         self.wgpu_shapes.enabled = self.play_state.turn_marker_status();
 
-        if let Some(state) = self.state_stack.last() {
-            match state {
-                State::InitPlay => {
-                    self.play_state.init();
-                    self.state_stack.pop();
-                }
-                State::ClearPlay => {
-                    self.play_state.clear();
-                    self.state_stack.pop();
+        // The music fades out with the screen between matches.
+        let track = match self.state_stack.last() {
+            Some(State::FadeOut | State::ClearPlay) => None,
+            _ => Some(self.play_state.track()),
+        };
+        self.audio.play_music(track)?;
+        self.audio.update(dt)?;
+
+        let Some(&state) = self.state_stack.last() else {
+            return Ok(());
+        };
+        match state {
+            State::InitPlay => {
+                self.play_state.init();
+                self.state_stack.pop();
+            }
+            State::ClearPlay => {
+                self.play_state.clear();
+                self.state_stack.pop();
+            }
+            State::Play => {
+                self.play_state.update(ctx, &mut self.events);
+                for event in self.play_state.publish() {
+                    self.audio.notify(&event);
                 }
-                State::Play => {
-                    if let Some(e) = self.play_state.update(ctx) {
-                        match e {
-                            Event::PlaySound(Sfx::Fanfare) => {
-                                self.sound_manager.bgm.stop(ctx)?;
-                                self.sound_manager.victory.play(ctx)?;
-                            }
-                            Event::PlaySound(Sfx::Flip) => {
-                                self.sound_manager.flip_card.play_detached(ctx)?;
-                            }
-                            Event::PlaySound(Sfx::Move) => {
-                                self.sound_manager.move_card.play_detached(ctx)?;
-                            }
-                            Event::PlaySound(Sfx::Select) => {
-                                self.sound_manager.select.play_detached(ctx)?;
-                            }
-                            Event::PlaySound(Sfx::Cancel) => {
-                                self.sound_manager.cancel.play_detached(ctx)?;
-                            }
-
-                            Event::Finished => {
-                                self.state_stack.push(State::InitPlay);
-                                self.state_stack.push(State::FadeIn);
-                                self.state_stack.push(State::ClearPlay);
-                                self.state_stack.push(State::FadeOut);
-                            }
-                            Event::Quit => ctx.request_quit(),
-                            _ => {}
-                        }
-                    }
+            }
+            State::FadeOut => self.fade_state.fade_out_update(dt, &mut self.events),
+            State::FadeIn => self.fade_state.fade_in_update(dt, &mut self.events),
+        }
+        for event in self.events.drain() {
+            match (state, event) {
+                (_, Event::PlaySound(sfx)) => self.audio.play_sfx(sfx)?,
+                (_, Event::SettingsChanged) => {
+                    self.apply_settings();
+                    self.audio.play_sfx(Sfx::Select)?;
                 }
-                State::FadeOut => {
-                    // if !self.sound_manager.bgm.stopped() {
-                    //     self.sound_manager.bgm.stop(ctx)?;
-                    // }
-
-                    self.sound_manager
-                        .bgm
-                        .set_volume(self.sound_manager.bgm.volume() - dt / 4.0);
-                    self.sound_manager
-                        .victory
-                        .set_volume(self.sound_manager.victory.volume() - dt / 4.0);
-                    if let Some(e) = self.fade_state.fade_out_update(dt) {
-                        match e {
-                            Event::Finished => {
-                                self.sound_manager.victory.stop(ctx)?;
-                                self.state_stack.pop();
-                            }
-                            _ => unreachable!(),
-                        }
-                    }
+                (State::Play, Event::Finished) => {
+                    self.transition(State::ClearPlay, State::InitPlay);
                 }
-                State::FadeIn => {
-                    if self.sound_manager.bgm.stopped() {
-                        self.sound_manager.bgm.play(ctx)?;
-                    }
-                    self.sound_manager
-                        .bgm
-                        .set_volume(self.sound_manager.bgm.volume() + dt / 4.0);
-                    self.sound_manager
-                        .victory
-                        .set_volume(self.sound_manager.victory.volume() + dt / 4.0);
-                    if let Some(e) = self.fade_state.fade_in_update(dt) {
-                        match e {
-                            Event::Finished => {
-                                self.state_stack.pop();
-                            }
-                            _ => unreachable!(),
-                        }
-                    }
+                (State::FadeIn | State::FadeOut, Event::Finished) => {
+                    self.state_stack.pop();
                 }
+                (_, Event::Quit) => ctx.request_quit(),
+                (state, event) => unreachable!("{event:?} on {state:?}"),
             }
         }
+        self.run_hooks();
         Ok(())
     }
 }
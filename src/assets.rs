@@ -0,0 +1,100 @@
+use ggez::graphics::{FontData, Image};
+use ggez::{Context, GameResult};
+use serde::de::DeserializeOwned;
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::error::{self, AssetError};
+
+/// Points the game at an assets directory instead of the embedded bundle.
+pub const ASSETS_DIR_VAR: &str = "TRIPLE_TRIAD_ASSETS";
+
+macro_rules! embed {
+    ($($name: literal),+ $(,)?) => {
+        &[$(($name, include_bytes!(concat!("../assets/", $name)))),+]
+    };
+}
+
+const EMBEDDED: &[(&str, &[u8])] = embed![
+    "achievements.json",
+    "audio.json",
+    "bloom_shader.glsl",
+    "board.png",
+    "campaign.json",
+    "card_sprites.json",
+    "card_sprites.png",
+    "cards.json",
+    "crt_shader.glsl",
+    "elem_animations.png",
+    "opponents.json",
+    "scanlines_shader.glsl",
+    "seed-computer.ttf",
+    "sfx/src_assets_sounds_cancel.mp3",
+    "sfx/src_assets_sounds_flip_card.mp3",
+    "sfx/src_assets_sounds_move_card.mp3",
+    "sfx/src_assets_sounds_music.mp3",
+    "sfx/src_assets_sounds_select.mp3",
+    "sfx/src_assets_sounds_selector.mp3",
+    "sfx/src_assets_sounds_special.mp3",
+    "sfx/src_assets_sounds_victory.mp3",
+];
+
+/// Where the game reads its assets from.
+///
+/// Release builds carry every asset inside the executable. During development
+/// `TRIPLE_TRIAD_ASSETS` can point at a directory, its files take precedence
+/// over the embedded ones.
+#[derive(Debug, Clone)]
+pub enum AssetSource {
+    Embedded,
+    Directory(PathBuf),
+}
+
+impl AssetSource {
+    pub fn from_env() -> Self {
+        std::env::var_os(ASSETS_DIR_VAR).map_or(Self::Embedded, |dir| Self::Directory(dir.into()))
+    }
+
+    pub fn read(&self, name: &str) -> Result<Cow<'static, [u8]>, AssetError> {
+        if let Self::Directory(dir) = self {
+            let path = dir.join(name);
+            if path.is_file() {
+                return std::fs::read(path)
+                    .map(Cow::Owned)
+                    .map_err(|source| AssetError::Io {
+                        file: name.into(),
+                        source,
+                    });
+            }
+        }
+
+        EMBEDDED
+            .iter()
+            .find(|(file, _)| *file == name)
+            .map(|(_, bytes)| Cow::Borrowed(*bytes))
+            .ok_or_else(|| AssetError::Io {
+                file: name.into(),
+                source: std::io::ErrorKind::NotFound.into(),
+            })
+    }
+
+    pub fn read_to_string(&self, name: &str) -> Result<String, AssetError> {
+        String::from_utf8(self.read(name)?.into_owned()).map_err(|e| AssetError::Io {
+            file: name.into(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })
+    }
+
+    pub fn json<T: DeserializeOwned>(&self, name: &str) -> Result<T, AssetError> {
+        error::from_json_reader(name, self.read(name)?.as_ref())
+    }
+
+    pub fn image(&self, ctx: &Context, name: &str) -> GameResult<Image> {
+        Image::from_bytes(ctx, &self.read(name)?)
+    }
+
+    pub fn font(&self, name: &str) -> GameResult<FontData> {
+        FontData::from_vec(self.read(name)?.into_owned())
+    }
+}
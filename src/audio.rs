@@ -0,0 +1,486 @@
+use ggez::{GameError, GameResult};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use serde::Deserialize;
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use crate::assets::AssetSource;
+use crate::config;
+use crate::error::AssetError;
+use crate::events::{GameEvent, Subscriber};
+use crate::utils::Sfx;
+
+pub const AUDIO_MANIFEST: &str = "audio.json";
+
+// The music is mixed below the sound effects.
+const MUSIC_MIX: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    Menu,
+    Duel,
+    Victory,
+    Defeat,
+}
+
+impl Track {
+    pub const ALL: [Self; 4] = [Self::Menu, Self::Duel, Self::Victory, Self::Defeat];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Menu => "menu",
+            Self::Duel => "duel",
+            Self::Victory => "victory",
+            Self::Defeat => "defeat",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SoundEntry {
+    file: String,
+    /// Plays the file faster and higher above 1, so one file can serve as
+    /// several cues.
+    #[serde(default = "default_pitch")]
+    pitch: f32,
+}
+
+const fn default_pitch() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Debug)]
+struct TrackEntry {
+    file: String,
+    #[serde(default)]
+    repeat: bool,
+}
+
+/// `audio.json`, maps cue names to files. A cue missing from it is silent.
+#[derive(Deserialize, Debug, Default)]
+struct Manifest {
+    #[serde(default)]
+    sounds: HashMap<String, SoundEntry>,
+    #[serde(default)]
+    music: HashMap<String, TrackEntry>,
+}
+
+/// Volume of a group of sounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bus {
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Bus {
+    pub fn gain(self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+/// A music file that is playing, `fade` goes from 0 to 1 as it comes in.
+#[derive(Debug, PartialEq)]
+struct Playing {
+    file: String,
+    fade: f32,
+}
+
+/// The music files playing and how far each one faded in, no sound involved.
+#[derive(Debug, Default)]
+struct Crossfade {
+    current: Option<Playing>,
+    fading: Vec<Playing>,
+}
+
+impl Crossfade {
+    /// Fades the current file out and `file` in. Returns `file` if it has
+    /// to start playing, a file still fading out comes back instead.
+    fn switch(&mut self, file: Option<&str>) -> Option<String> {
+        if file == self.current.as_ref().map(|playing| playing.file.as_str()) {
+            return None;
+        }
+        if let Some(playing) = self.current.take() {
+            self.fading.push(playing);
+        }
+        let file = file?;
+        if let Some(i) = self.fading.iter().position(|playing| playing.file == file) {
+            self.current = Some(self.fading.swap_remove(i));
+            return None;
+        }
+        self.current = Some(Playing {
+            file: file.into(),
+            fade: 0.0,
+        });
+        Some(file.into())
+    }
+
+    /// Moves every fade by `step`. Returns the files that faded out.
+    fn step(&mut self, step: f32) -> Vec<String> {
+        if let Some(playing) = &mut self.current {
+            playing.fade = (playing.fade + step).min(1.0);
+        }
+        for playing in &mut self.fading {
+            playing.fade = (playing.fade - step).max(0.0);
+        }
+        let (done, fading) = std::mem::take(&mut self.fading)
+            .into_iter()
+            .partition(|playing| playing.fade <= 0.0);
+        self.fading = fading;
+        done.into_iter()
+            .map(|playing: Playing| playing.file)
+            .collect()
+    }
+
+    fn playing(&self) -> impl Iterator<Item = &Playing> {
+        self.current.iter().chain(&self.fading)
+    }
+}
+
+/// The bytes of a sound file that decodes, shared by every playback of it.
+#[derive(Debug)]
+struct SoundData(Arc<[u8]>);
+
+impl SoundData {
+    fn decoder(&self) -> Decoder<Cursor<Arc<[u8]>>> {
+        Decoder::new(Cursor::new(Arc::clone(&self.0))).expect("Sounds are checked when loaded")
+    }
+}
+
+struct Sound {
+    data: SoundData,
+    pitch: f32,
+}
+
+/// A music file, `sink` is set while it plays.
+struct Music {
+    data: SoundData,
+    repeat: bool,
+    sink: Option<Sink>,
+}
+
+/// The audio device, the stream stops playing once dropped.
+struct Output {
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+}
+
+/// Plays the sound and music cues listed in the manifest.
+///
+/// Music goes through crossfades: asking for another track fades the current
+/// one out while the new one comes in. Tracks sharing a file keep playing
+/// across the switch. A manager without an output, see `silent`, keeps track
+/// of the music without playing anything.
+pub struct AudioManager {
+    output: Option<Output>,
+    sounds: HashMap<String, Sound>,
+    /// Track name to file, the music is shared per file.
+    tracks: HashMap<String, String>,
+    music_sources: HashMap<String, Music>,
+    music: Bus,
+    sfx: Bus,
+    crossfade: f32,
+    fades: Crossfade,
+    /// Sounds of the gameplay events, played on the next update.
+    queued: Vec<Sfx>,
+}
+
+impl AudioManager {
+    /// Loads every file named in the manifest and opens the default audio
+    /// device. A missing or undecodable file is an error, a missing device
+    /// only disables sound.
+    pub fn load(assets: &AssetSource) -> GameResult<Self> {
+        let manifest: Manifest = assets.json(AUDIO_MANIFEST)?;
+        let mut manager = Self::with_manifest(assets, manifest)?;
+        match OutputStream::try_default() {
+            Ok((stream, handle)) => {
+                manager.output = Some(Output {
+                    _stream: stream,
+                    handle,
+                });
+                Ok(manager)
+            }
+            Err(e) => {
+                eprintln!("Audio disabled: {e}");
+                Ok(Self::silent())
+            }
+        }
+    }
+
+    /// A manager that plays nothing.
+    pub fn silent() -> Self {
+        Self {
+            output: None,
+            sounds: HashMap::new(),
+            tracks: HashMap::new(),
+            music_sources: HashMap::new(),
+            music: Bus {
+                volume: 1.0,
+                muted: false,
+            },
+            sfx: Bus {
+                volume: 1.0,
+                muted: false,
+            },
+            crossfade: 1.0,
+            fades: Crossfade::default(),
+            queued: Vec::new(),
+        }
+    }
+
+    fn with_manifest(assets: &AssetSource, manifest: Manifest) -> Result<Self, AssetError> {
+        let source = |file: &str| assets.read(file).and_then(|data| sound_data(file, &data));
+
+        let mut manager = Self::silent();
+        for (name, entry) in manifest.sounds {
+            let sound = Sound {
+                data: source(&entry.file)?,
+                pitch: entry.pitch,
+            };
+            manager.sounds.insert(name, sound);
+        }
+        for (name, entry) in manifest.music {
+            if !manager.music_sources.contains_key(&entry.file) {
+                let music = Music {
+                    data: source(&entry.file)?,
+                    repeat: entry.repeat,
+                    sink: None,
+                };
+                manager.music_sources.insert(entry.file.clone(), music);
+            }
+            manager.tracks.insert(name, entry.file);
+        }
+        Ok(manager)
+    }
+
+    pub fn apply_settings(&mut self, audio: &config::Audio, crossfade: f32) {
+        self.music = Bus {
+            volume: audio.music_volume(),
+            muted: audio.mute_music,
+        };
+        self.sfx = Bus {
+            volume: audio.sfx_volume(),
+            muted: audio.mute_sfx,
+        };
+        self.crossfade = crossfade;
+    }
+
+    pub fn play_sfx(&mut self, sfx: Sfx) -> GameResult {
+        let gain = self.sfx.gain();
+        match (&self.output, self.sounds.get(sfx.name())) {
+            (Some(output), Some(sound)) if gain > 0.0 => {
+                let source = sound.data.decoder().speed(sound.pitch).amplify(gain);
+                output
+                    .handle
+                    .play_raw(source.convert_samples())
+                    .map_err(play_error)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Crossfades to `track`, `None` fades the music out.
+    pub fn play_music(&mut self, track: Option<Track>) -> GameResult {
+        let file = track.and_then(|track| self.tracks.get(track.name()));
+        let Some(file) = self.fades.switch(file.map(String::as_str)) else {
+            return Ok(());
+        };
+        if let (Some(output), Some(music)) = (&self.output, self.music_sources.get_mut(&file)) {
+            let sink = Sink::try_new(&output.handle).map_err(play_error)?;
+            sink.set_volume(0.0);
+            if music.repeat {
+                sink.append(music.data.decoder().repeat_infinite());
+            } else {
+                sink.append(music.data.decoder());
+            }
+            music.sink = Some(sink);
+        }
+        Ok(())
+    }
+
+    /// Plays the queued sounds, moves the crossfades along and stops the
+    /// tracks that faded out.
+    pub fn update(&mut self, dt: f32) -> GameResult {
+        for sfx in std::mem::take(&mut self.queued) {
+            self.play_sfx(sfx)?;
+        }
+
+        let step = dt / self.crossfade.max(f32::EPSILON);
+        let gain = MUSIC_MIX * self.music.gain();
+
+        for file in self.fades.step(step) {
+            if let Some(sink) = self
+                .music_sources
+                .get_mut(&file)
+                .and_then(|music| music.sink.take())
+            {
+                sink.stop();
+            }
+        }
+        for playing in self.fades.playing() {
+            if let Some(sink) = self
+                .music_sources
+                .get(&playing.file)
+                .and_then(|music| music.sink.as_ref())
+            {
+                sink.set_volume(playing.fade * gain);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Subscriber for AudioManager {
+    /// Queues the sound of the event. A sound plays once however many cards
+    /// triggered it in the frame.
+    fn notify(&mut self, event: &GameEvent) {
+        if let Some(sfx) = event.sfx() {
+            if !self.queued.contains(&sfx) {
+                self.queued.push(sfx);
+            }
+        }
+    }
+}
+
+/// The bytes of `file`, checked to be a sound rodio can decode.
+fn sound_data(file: &str, bytes: &[u8]) -> Result<SoundData, AssetError> {
+    let data: Arc<[u8]> = bytes.into();
+    match Decoder::new(Cursor::new(Arc::clone(&data))) {
+        Ok(_) => Ok(SoundData(data)),
+        Err(e) => Err(AssetError::Invalid {
+            file: file.into(),
+            message: format!("not a sound that can be decoded: {e}"),
+        }),
+    }
+}
+
+fn play_error(e: rodio::PlayError) -> GameError {
+    GameError::AudioError(format!("Audio playing error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fades(crossfade: &Crossfade) -> Vec<(&str, f32)> {
+        crossfade
+            .playing()
+            .map(|playing| (playing.file.as_str(), playing.fade))
+            .collect()
+    }
+
+    fn files(manifest: &Manifest) -> impl Iterator<Item = &String> {
+        manifest
+            .sounds
+            .values()
+            .map(|entry| &entry.file)
+            .chain(manifest.music.values().map(|entry| &entry.file))
+    }
+
+    #[test]
+    fn crossfade_between_tracks() {
+        let mut crossfade = Crossfade::default();
+        assert_eq!(crossfade.switch(Some("menu")), Some("menu".into()));
+        assert!(crossfade.step(0.5).is_empty());
+        assert_eq!(fades(&crossfade), [("menu", 0.5)]);
+
+        assert_eq!(crossfade.switch(Some("duel")), Some("duel".into()));
+        assert!(crossfade.step(0.25).is_empty());
+        assert_eq!(fades(&crossfade), [("duel", 0.25), ("menu", 0.25)]);
+
+        assert_eq!(crossfade.step(0.25), ["menu"]);
+        assert_eq!(fades(&crossfade), [("duel", 0.5)]);
+        crossfade.step(1.0);
+        assert_eq!(fades(&crossfade), [("duel", 1.0)]);
+    }
+
+    #[test]
+    fn same_file_keeps_playing() {
+        let mut crossfade = Crossfade::default();
+        crossfade.switch(Some("music"));
+        crossfade.step(1.0);
+        assert_eq!(crossfade.switch(Some("music")), None);
+        assert_eq!(fades(&crossfade), [("music", 1.0)]);
+    }
+
+    #[test]
+    fn fading_track_comes_back() {
+        let mut crossfade = Crossfade::default();
+        crossfade.switch(Some("menu"));
+        crossfade.step(1.0);
+        crossfade.switch(Some("victory"));
+        crossfade.step(0.25);
+        assert_eq!(crossfade.switch(Some("menu")), None);
+        assert_eq!(fades(&crossfade), [("menu", 0.75), ("victory", 0.25)]);
+    }
+
+    #[test]
+    fn no_track_fades_out() {
+        let mut crossfade = Crossfade::default();
+        crossfade.switch(Some("menu"));
+        crossfade.step(1.0);
+        assert_eq!(crossfade.switch(None), None);
+        assert_eq!(crossfade.step(1.0), ["menu"]);
+        assert!(fades(&crossfade).is_empty());
+    }
+
+    #[test]
+    fn muted_bus_is_silent() {
+        let bus = Bus {
+            volume: 0.7,
+            muted: false,
+        };
+        assert_eq!(bus.gain(), 0.7);
+        assert_eq!(Bus { muted: true, ..bus }.gain(), 0.0);
+    }
+
+    #[test]
+    fn undecodable_sound_is_an_asset_error() {
+        let error = sound_data("sfx/broken.mp3", b"not a sound").unwrap_err();
+        assert!(error.to_string().starts_with("sfx/broken.mp3"));
+    }
+
+    #[test]
+    fn manifest_covers_every_cue() {
+        let assets = AssetSource::Embedded;
+        let manifest: Manifest = assets.json(AUDIO_MANIFEST).unwrap();
+        let sfx = [
+            Sfx::Move,
+            Sfx::Select,
+            Sfx::Cancel,
+            Sfx::Flip,
+            Sfx::Hover,
+            Sfx::Combo,
+            Sfx::ElementalBonus,
+            Sfx::ElementalMalus,
+            Sfx::Defeat,
+        ];
+        for sfx in sfx {
+            assert!(manifest.sounds.contains_key(sfx.name()), "{}", sfx.name());
+        }
+        for track in Track::ALL {
+            assert!(
+                manifest.music.contains_key(track.name()),
+                "{}",
+                track.name()
+            );
+        }
+        for file in files(&manifest) {
+            assert!(!assets.read(file).unwrap().is_empty(), "{file}");
+        }
+    }
+
+    #[test]
+    fn manifest_files_decode() {
+        let assets = AssetSource::Embedded;
+        let manifest: Manifest = assets.json(AUDIO_MANIFEST).unwrap();
+        for file in files(&manifest) {
+            sound_data(file, &assets.read(file).unwrap()).unwrap();
+        }
+    }
+}
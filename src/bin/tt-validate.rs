@@ -0,0 +1,63 @@
+//! Checks a card atlas for problems the game would choke on.
+//!
+//! Usage: `tt-validate [--pack] [DIR]`, where `DIR` holds `cards.json` and
+//! `card_sprites.json` (`assets` by default). `--pack` validates a card pack
+//! from the mods directory instead of the base atlas.
+
+use ab_glyph::FontRef;
+use serde_json::Value;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use triple_triad::mods::{PACK_CARDS_FILE, PACK_SPRITES_DATA};
+use triple_triad::validate;
+
+const DEFAULT_DIR: &str = "assets";
+const FF8_FONT: &[u8] = include_bytes!("../../assets/seed-computer.ttf");
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn main() -> ExitCode {
+    let mut pack = false;
+    let mut dir = PathBuf::from(DEFAULT_DIR);
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--pack" => pack = true,
+            "-h" | "--help" => {
+                println!("Usage: tt-validate [--pack] [DIR]");
+                return ExitCode::SUCCESS;
+            }
+            _ => dir = PathBuf::from(arg),
+        }
+    }
+
+    let font = FontRef::try_from_slice(FF8_FONT).expect("Bundled font is invalid");
+    let (cards, sprites) = match (
+        read_json(&dir.join(PACK_CARDS_FILE)),
+        read_json(&dir.join(PACK_SPRITES_DATA)),
+    ) {
+        (Ok(cards), Ok(sprites)) => (cards, sprites),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("error: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let problems = validate::validate(&cards, &sprites, &font, pack);
+    for problem in &problems {
+        println!("{}: {problem}", dir.display());
+    }
+
+    if problems.is_empty() {
+        println!("{}: ok", dir.display());
+        ExitCode::SUCCESS
+    } else {
+        println!("{}: {} problem(s) found", dir.display(), problems.len());
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+
+use crate::assets::AssetSource;
+use crate::core::{CardAtlas, Rules};
+use crate::deck::DECK_SIZE;
+use crate::error::AssetError;
+use crate::opponents::DeckSpec;
+use crate::persist;
+
+pub const CAMPAIGN_FILE: &str = "campaign.json";
+pub const PROGRESS_FILE: &str = "campaign_progress.json";
+
+/// How the AI plays against the player.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Personality {
+    /// Search depth of the AI, 1 to 5.
+    pub difficulty: usize,
+    /// Seconds the AI waits before each move, the settings value if missing.
+    pub think_delay: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CampaignOpponent {
+    pub id: String,
+    pub name: String,
+    pub deck: DeckSpec,
+    #[serde(default)]
+    pub rules: Rules,
+    pub ai: Personality,
+    /// Card added to the collection when the opponent is beaten.
+    pub reward: String,
+}
+
+/// The ladder of opponents in `campaign.json`, each one is unlocked by
+/// beating the previous one.
+#[derive(Deserialize, Debug, Default)]
+pub struct Campaign {
+    pub opponents: Vec<CampaignOpponent>,
+}
+
+impl Campaign {
+    /// Reads the campaign and checks that every deck can deal a hand of
+    /// known cards.
+    pub fn load(assets: &AssetSource, card_atlas: &CardAtlas) -> Result<Self, AssetError> {
+        let campaign: Self = assets.json(CAMPAIGN_FILE)?;
+        for opponent in &campaign.opponents {
+            let invalid = |message| AssetError::Invalid {
+                file: CAMPAIGN_FILE.into(),
+                message: format!("{}: {message}", opponent.id),
+            };
+            opponent.deck.check(card_atlas).map_err(invalid)?;
+            if card_atlas.position(&opponent.reward).is_none() {
+                return Err(invalid(format!("unknown card `{}`", opponent.reward)));
+            }
+        }
+        Ok(campaign)
+    }
+
+    /// Deals the hand of opponent `n`.
+    pub fn new_hand(&self, n: usize, card_atlas: &CardAtlas) -> [usize; DECK_SIZE] {
+        self.opponents[n].deck.deal(card_atlas)
+    }
+}
+
+/// The opponents the player has beaten, kept in the user data directory.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Progress {
+    #[serde(skip)]
+    path: PathBuf,
+    pub beaten: Vec<String>,
+}
+
+impl Progress {
+    /// Reads the progress from `dir`, a new campaign until the first save.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(PROGRESS_FILE);
+        let mut progress: Self = persist::read(&path).unwrap_or_default();
+        progress.path = path;
+        progress
+    }
+
+    pub fn save(&self) -> Result<(), AssetError> {
+        persist::write(&self.path, self)
+    }
+
+    pub fn is_beaten(&self, opponent: &CampaignOpponent) -> bool {
+        self.beaten.contains(&opponent.id)
+    }
+
+    /// The first opponent is always open, the others once the one before
+    /// them is beaten.
+    pub fn is_unlocked(&self, campaign: &Campaign, n: usize) -> bool {
+        n == 0 || self.is_beaten(&campaign.opponents[n - 1])
+    }
+
+    /// Marks the opponent as beaten, true the first time.
+    pub fn record_win(&mut self, opponent: &CampaignOpponent) -> bool {
+        if self.is_beaten(opponent) {
+            return false;
+        }
+        self.beaten.push(opponent.id.clone());
+        if let Err(e) = self.save() {
+            eprintln!("{e}");
+        }
+        true
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::AssetError;
+use crate::persist;
+
+pub const COLLECTION_FILE: &str = "collection.json";
+
+/// What the player knows about one card.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct CardRecord {
+    /// Copies won by capturing the card in a match the player won, or as a
+    /// campaign reward.
+    pub owned: u32,
+    /// Matches the card was played in.
+    pub seen: u32,
+    /// Times the player captured it.
+    pub captured: u32,
+}
+
+/// Per card records kept in the user data directory, keyed like decks by
+/// `pack:id`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Collection {
+    #[serde(skip)]
+    path: PathBuf,
+    pub cards: HashMap<String, CardRecord>,
+}
+
+impl Collection {
+    /// Reads the collection from `dir`, empty until the first save.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(COLLECTION_FILE);
+        let mut collection: Self = persist::read(&path).unwrap_or_default();
+        collection.path = path;
+        collection
+    }
+
+    pub fn save(&self) -> Result<(), AssetError> {
+        persist::write(&self.path, self)
+    }
+
+    pub fn get(&self, key: &str) -> CardRecord {
+        self.cards.get(key).copied().unwrap_or_default()
+    }
+
+    /// Adds a copy of the card won outside of a match capture.
+    pub fn add_reward(&mut self, key: &str) {
+        self.cards.entry(key.into()).or_default().owned += 1;
+        if let Err(e) = self.save() {
+            eprintln!("{e}");
+        }
+    }
+
+    /// Records the end of a match: every card in `played` was seen, the
+    /// `captured` ones are also owned if the player `won`.
+    pub fn record_match<'a>(
+        &mut self,
+        played: impl IntoIterator<Item = &'a str>,
+        captured: impl IntoIterator<Item = &'a str>,
+        won: bool,
+    ) {
+        for key in played {
+            self.cards.entry(key.into()).or_default().seen += 1;
+        }
+        for key in captured {
+            let record = self.cards.entry(key.into()).or_default();
+            record.captured += 1;
+            if won {
+                record.owned += 1;
+            }
+        }
+        if let Err(e) = self.save() {
+            eprintln!("{e}");
+        }
+    }
+}
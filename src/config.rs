@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+
+use crate::core::Rules;
+use crate::error::AssetError;
+use crate::graphics::post::Effect;
+
+pub const CONFIG_FILE: &str = "config.toml";
+/// Limits of the values the settings screen steps through, applied to the
+/// file too.
+pub const VOLUME_RANGE: [f32; 2] = [0.0, 1.0];
+pub const INTENSITY_RANGE: [f32; 2] = [0.0, 1.0];
+pub const ANIMATION_SPEED_RANGE: [f32; 2] = [0.25, 3.0];
+pub const AI_DELAY_RANGE: [f32; 2] = [0.0, 3.0];
+pub const TOOLTIP_DELAY_RANGE: [f32; 2] = [0.0, 2.0];
+pub const FADE_DURATION_RANGE: [f32; 2] = [0.5, 4.0];
+pub const MAX_DIFFICULTY: usize = 3;
+/// Highest Sudden Death limit, 0 stands for no limit.
+pub const MAX_SUDDEN_DEATH_ROUNDS: u32 = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Audio {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub mute_music: bool,
+    pub mute_sfx: bool,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+            sfx: 1.0,
+            mute_music: false,
+            mute_sfx: false,
+        }
+    }
+}
+
+impl Audio {
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Crt {
+    /// Lower bends the screen more.
+    pub curvature: f32,
+    /// Number of vertical and horizontal lines.
+    pub resolution: [f32; 2],
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self {
+            curvature: 10.0,
+            resolution: [320.0, 240.0],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Scanlines {
+    pub lines: f32,
+    /// Higher makes the dark part of each line darker.
+    pub opacity: f32,
+}
+
+impl Default for Scanlines {
+    fn default() -> Self {
+        Self {
+            lines: 300.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Bloom {
+    /// Brightness, between 0 and 1, above which pixels start to glow.
+    pub threshold: f32,
+    /// Distance in pixels between the samples of the glow.
+    pub radius: f32,
+    pub strength: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            threshold: 0.6,
+            radius: 2.0,
+            strength: 1.5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "VideoFile")]
+pub struct Video {
+    /// Post-processing passes, applied in order.
+    pub effects: Vec<Effect>,
+    #[serde(alias = "crt_intensity")]
+    pub intensity: f32,
+    pub crt: Crt,
+    pub scanlines: Scanlines,
+    pub bloom: Bloom,
+}
+
+impl Default for Video {
+    fn default() -> Self {
+        Self {
+            effects: vec![Effect::Crt],
+            intensity: 1.0,
+            crt: Crt::default(),
+            scanlines: Scanlines::default(),
+            bloom: Bloom::default(),
+        }
+    }
+}
+
+/// `[video]` as read from `config.toml`. `crt` used to be a bool turning the
+/// CRT shader on or off, files written back then still load.
+#[derive(Deserialize)]
+#[serde(default)]
+struct VideoFile {
+    effects: Option<Vec<Effect>>,
+    #[serde(alias = "crt_intensity")]
+    intensity: f32,
+    crt: CrtFile,
+    scanlines: Scanlines,
+    bloom: Bloom,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CrtFile {
+    Enabled(bool),
+    Settings(Crt),
+}
+
+impl Default for VideoFile {
+    fn default() -> Self {
+        let video = Video::default();
+        Self {
+            effects: None,
+            intensity: video.intensity,
+            crt: CrtFile::Settings(video.crt),
+            scanlines: video.scanlines,
+            bloom: video.bloom,
+        }
+    }
+}
+
+impl From<VideoFile> for Video {
+    fn from(file: VideoFile) -> Self {
+        let (crt, enabled) = match file.crt {
+            CrtFile::Enabled(enabled) => (Crt::default(), enabled),
+            CrtFile::Settings(crt) => (crt, true),
+        };
+        let effects = file.effects.unwrap_or_else(|| {
+            if enabled {
+                Self::default().effects
+            } else {
+                Vec::new()
+            }
+        });
+        Self {
+            effects,
+            intensity: file.intensity,
+            crt,
+            scanlines: file.scanlines,
+            bloom: file.bloom,
+        }
+    }
+}
+
+/// Who plays first in a round.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FirstTurn {
+    /// The cursor picks a side at random.
+    #[default]
+    Random,
+    /// The side that lost the player's last match, a random one after a
+    /// draw and in Sudden Death.
+    Loser,
+    /// Always the player, for practice.
+    Player,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Gameplay {
+    /// Multiplies the speed of card animations.
+    pub animation_speed: f32,
+    /// Seconds the AI waits before each move.
+    pub ai_delay: f32,
+    /// Seconds the mouse has to rest on a card before its name shows up.
+    pub tooltip_delay: f32,
+    /// Seconds a fade between matches takes.
+    pub fade_duration: f32,
+    pub difficulty: usize,
+    /// Sudden Death rounds dealt before a tie stands as a draw, 0 deals them
+    /// until someone wins.
+    pub max_sudden_death_rounds: u32,
+    pub first_turn: FirstTurn,
+    /// Shows the edges and ranks that decided every capture.
+    pub explain_captures: bool,
+    /// Last, TOML writes tables after the plain values.
+    pub rules: Rules,
+}
+
+impl Default for Gameplay {
+    fn default() -> Self {
+        Self {
+            animation_speed: 1.0,
+            ai_delay: 1.0,
+            tooltip_delay: 0.5,
+            fade_duration: 2.0,
+            difficulty: 1,
+            max_sudden_death_rounds: 5,
+            first_turn: FirstTurn::Random,
+            explain_captures: false,
+            rules: Rules::default(),
+        }
+    }
+}
+
+/// Settings kept in `config.toml` in the user config directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    #[serde(skip)]
+    path: PathBuf,
+    pub audio: Audio,
+    pub video: Video,
+    pub gameplay: Gameplay,
+}
+
+impl Config {
+    /// Reads the config from `dir`, missing values take their default. An
+    /// unreadable file is reported and replaced on the next save.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(CONFIG_FILE);
+        let mut config = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|source| {
+                eprintln!(
+                    "{}",
+                    AssetError::Toml {
+                        file: CONFIG_FILE.into(),
+                        source,
+                    }
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        config.limit();
+        config.path = path;
+        config
+    }
+
+    /// Brings values edited by hand back within what the settings screen
+    /// offers.
+    fn limit(&mut self) {
+        let audio = &mut self.audio;
+        for volume in [&mut audio.master, &mut audio.music, &mut audio.sfx] {
+            *volume = limit(*volume, VOLUME_RANGE);
+        }
+        self.video.intensity = limit(self.video.intensity, INTENSITY_RANGE);
+        let gameplay = &mut self.gameplay;
+        gameplay.animation_speed = limit(gameplay.animation_speed, ANIMATION_SPEED_RANGE);
+        gameplay.ai_delay = limit(gameplay.ai_delay, AI_DELAY_RANGE);
+        gameplay.tooltip_delay = limit(gameplay.tooltip_delay, TOOLTIP_DELAY_RANGE);
+        gameplay.fade_duration = limit(gameplay.fade_duration, FADE_DURATION_RANGE);
+        gameplay.difficulty = gameplay.difficulty.clamp(1, MAX_DIFFICULTY);
+        gameplay.max_sudden_death_rounds = gameplay
+            .max_sudden_death_rounds
+            .min(MAX_SUDDEN_DEATH_ROUNDS);
+    }
+
+    pub fn save(&self) -> Result<(), AssetError> {
+        let io_error = |source| AssetError::Io {
+            file: CONFIG_FILE.into(),
+            source,
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let text = toml::to_string_pretty(self).expect("Config is always serializable");
+        std::fs::write(&self.path, text).map_err(io_error)
+    }
+}
+
+/// Clamps `value` to `[min, max]`, NaN becomes `min`.
+fn limit(value: f32, [min, max]: [f32; 2]) -> f32 {
+    if value.is_nan() {
+        min
+    } else {
+        value.clamp(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(text: &str) -> Video {
+        toml::from_str::<Config>(text).unwrap().video
+    }
+
+    #[test]
+    fn missing_video_is_default() {
+        assert_eq!(video(""), Video::default());
+        assert_eq!(video("[video]"), Video::default());
+    }
+
+    #[test]
+    fn crt_settings() {
+        let video = video("[video]\neffects = [\"bloom\"]\n[video.crt]\ncurvature = 4.0\n");
+        assert_eq!(video.effects, vec![Effect::Bloom]);
+        assert_eq!(video.crt.curvature, 4.0);
+        assert_eq!(video.crt.resolution, Crt::default().resolution);
+    }
+
+    #[test]
+    fn crt_as_a_bool() {
+        let on = video("[video]\ncrt = true\ncrt_intensity = 0.5\n");
+        assert_eq!(on.effects, vec![Effect::Crt]);
+        assert_eq!(on.intensity, 0.5);
+        assert_eq!(on.crt, Crt::default());
+
+        let off = video("[video]\ncrt = false\n");
+        assert!(off.effects.is_empty());
+    }
+
+    #[test]
+    fn saved_config_loads_back() {
+        let mut config = Config::default();
+        config.video.effects = vec![Effect::Scanlines, Effect::Crt];
+        config.video.crt.curvature = 6.0;
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<Config>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn hand_edited_values_are_limited() {
+        let mut config: Config = toml::from_str(
+            "[audio]\nmaster = 3.0\n[gameplay]\nanimation_speed = 0.0\nai_delay = -1.0\n\
+             tooltip_delay = nan\nfade_duration = 60.0\ndifficulty = 9\n\
+             max_sudden_death_rounds = 100\n",
+        )
+        .unwrap();
+        config.limit();
+        assert_eq!(config.audio.master, 1.0);
+        let gameplay = &config.gameplay;
+        assert_eq!(gameplay.animation_speed, 0.25);
+        assert_eq!(gameplay.ai_delay, 0.0);
+        assert_eq!(gameplay.tooltip_delay, 0.0);
+        assert_eq!(gameplay.fade_duration, 4.0);
+        assert_eq!(gameplay.difficulty, MAX_DIFFICULTY);
+        assert_eq!(gameplay.max_sudden_death_rounds, MAX_SUDDEN_DEATH_ROUNDS);
+
+        let mut config = Config::default();
+        config.limit();
+        assert_eq!(config, Config::default());
+    }
+}
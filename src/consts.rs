@@ -24,6 +24,9 @@ pub const BLUE_SCORE_POS: [f32; 2] = [670.0, 516.0];
 
 pub const FONT_SIZE: f32 = 25.0;
 
+// Space left for a card name in the card picker.
+pub const CARD_NAME_MAX_WIDTH: f32 = 305.0;
+
 /*              Sprites               */
 
 pub const CARD_BACK_SPRITE_ID: usize = 110;
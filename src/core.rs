@@ -3,10 +3,15 @@ use rand::{
     Rng,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-#[derive(Clone, Debug, Default)]
+use crate::assets::AssetSource;
+use crate::error::AssetError;
+use crate::utils::Rule;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Rules {
     pub open: bool,
     pub random: bool,
@@ -17,22 +22,47 @@ pub struct Rules {
     pub sudden_death: bool,
 }
 
-#[derive(Clone, Debug, Copy)]
+impl Rules {
+    pub fn toggle(&mut self, rule: Rule) {
+        match rule {
+            Rule::Open => self.open = !self.open,
+            Rule::Elemental => self.elemental = !self.elemental,
+            Rule::Random => self.random = !self.random,
+            Rule::Same => self.same = !self.same,
+            Rule::Wall => self.same_wall = !self.same_wall,
+            Rule::Plus => self.plus = !self.plus,
+            Rule::SuddenDeath => self.sudden_death = !self.sudden_death,
+        }
+    }
+
+    pub const fn enabled(&self, rule: Rule) -> bool {
+        match rule {
+            Rule::Open => self.open,
+            Rule::Elemental => self.elemental,
+            Rule::Random => self.random,
+            Rule::Same => self.same,
+            Rule::Wall => self.same_wall,
+            Rule::Plus => self.plus,
+            Rule::SuddenDeath => self.sudden_death,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
 pub enum DuelOutcome {
     Win,
     Lose,
     Draw,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Suit {
     Red,
     Blue,
 }
-#[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 pub struct Card {
-    id: usize,
+    pub id: usize,
     pub name: String,
     pub level: u8,
     #[serde(rename = "powLeft")]
@@ -44,9 +74,13 @@ pub struct Card {
     #[serde(rename = "powBottom")]
     pub pow_bottom: u8,
     pub element: Option<Element>,
+    #[serde(skip)]
+    pub sprite_id: usize,
+    #[serde(skip)]
+    pub pack: String,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Element {
     Fire,
     Ice,
@@ -58,6 +92,19 @@ pub enum Element {
     Holy,
 }
 
+impl Element {
+    pub const ALL: [Self; 8] = [
+        Self::Fire,
+        Self::Ice,
+        Self::Thunder,
+        Self::Earth,
+        Self::Poison,
+        Self::Wind,
+        Self::Water,
+        Self::Holy,
+    ];
+}
+
 impl Distribution<Element> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Element {
         match rng.gen_range(0..=7) {
@@ -74,24 +121,69 @@ impl Distribution<Element> for Standard {
     }
 }
 
+/// Cycles a level filter: all levels, then each one in turn.
+pub fn next_level(levels: &[u8], level: Option<u8>) -> Option<u8> {
+    match level {
+        None => levels.first().copied(),
+        Some(level) => levels.iter().copied().find(|l| *l > level),
+    }
+}
+
+/// Cycles an element filter: all cards, cards without an element, then each
+/// element in turn.
+pub fn next_element(element: Option<Option<Element>>) -> Option<Option<Element>> {
+    match element {
+        None => Some(None),
+        Some(None) => Some(Some(Element::ALL[0])),
+        Some(Some(element)) => Element::ALL
+            .iter()
+            .position(|e| *e == element)
+            .and_then(|i| Element::ALL.get(i + 1))
+            .map(|e| Some(*e)),
+    }
+}
+
 #[serde_as]
 #[derive(Deserialize, Debug)]
 pub struct CardAtlas {
     pub cards: Vec<Card>,
 }
 
+pub const BASE_PACK: &str = "base";
+/// Cards from this level up are rare, they are mostly found in the hands of
+/// specific opponents.
+pub const RARE_LEVEL: u8 = 8;
+
 impl Card {
     pub const fn rank_as_slice(&self) -> [u8; 4] {
         [self.pow_top, self.pow_right, self.pow_bottom, self.pow_left]
     }
+
+    /// Returns the namespaced id of the card, e.g. `base:12`.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.pack, self.id)
+    }
 }
 impl CardAtlas {
-    pub fn parse_atlas_json(filename: &str) -> Self {
-        use std::fs::File;
-        use std::io::BufReader;
-        let path = std::path::PathBuf::from(filename);
-        let file = File::open(path).expect("Couldn't find the card atlas file");
-        let buf_reader = BufReader::new(file);
-        serde_json::from_reader(buf_reader).expect("Couldn't create the card atlas")
+    pub fn parse_atlas_json(assets: &AssetSource, filename: &str) -> Result<Self, AssetError> {
+        let mut atlas: Self = assets.json(filename)?;
+        for card in &mut atlas.cards {
+            card.sprite_id = card.id;
+            card.pack = BASE_PACK.into();
+        }
+        Ok(atlas)
+    }
+
+    /// Returns every level that has at least one card, in ascending order.
+    pub fn levels(&self) -> Vec<u8> {
+        let mut levels: Vec<u8> = self.cards.iter().map(|card| card.level).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        levels
+    }
+
+    /// Returns the index of the card with the given key, e.g. `base:12`.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.cards.iter().position(|card| card.key() == key)
     }
 }
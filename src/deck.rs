@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+
+use crate::core::CardAtlas;
+use crate::error::AssetError;
+use crate::persist;
+
+pub const DECKS_FILE: &str = "decks.json";
+pub const DECK_SIZE: usize = 5;
+pub const DECK_NAME_MAX_LEN: usize = 16;
+
+/// A named deck, cards are stored by key (`pack:id`) so decks survive card
+/// packs being added or removed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Deck {
+    pub name: String,
+    pub cards: Vec<String>,
+}
+
+impl Deck {
+    /// Returns the atlas index of every card, `None` if one of them is gone.
+    pub fn resolve(&self, card_atlas: &CardAtlas) -> Option<Vec<usize>> {
+        self.cards
+            .iter()
+            .map(|key| card_atlas.position(key))
+            .collect()
+    }
+
+    /// A deck can be played if it has five cards that are all in the atlas.
+    pub fn playable(&self, card_atlas: &CardAtlas) -> bool {
+        self.cards.len() == DECK_SIZE && self.resolve(card_atlas).is_some()
+    }
+}
+
+/// The saved decks, kept in the user data directory.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Decks {
+    #[serde(skip)]
+    path: PathBuf,
+    pub decks: Vec<Deck>,
+}
+
+impl Decks {
+    /// Reads the decks from `dir`, there are none until the first save.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(DECKS_FILE);
+        let mut decks: Self = persist::read(&path).unwrap_or_default();
+        decks.path = path;
+        decks
+    }
+
+    pub fn save(&self) -> Result<(), AssetError> {
+        persist::write(&self.path, self)
+    }
+
+    /// Returns the decks that can be played with the current atlas.
+    pub fn playable<'a>(&'a self, card_atlas: &'a CardAtlas) -> impl Iterator<Item = &'a Deck> {
+        self.decks.iter().filter(|deck| deck.playable(card_atlas))
+    }
+}
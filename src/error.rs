@@ -0,0 +1,76 @@
+use ggez::GameError;
+use serde::de::DeserializeOwned;
+
+use std::fmt;
+use std::io::Read;
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io {
+        file: String,
+        source: std::io::Error,
+    },
+    Json {
+        file: String,
+        path: String,
+        source: serde_json::Error,
+    },
+    Toml {
+        file: String,
+        source: toml::de::Error,
+    },
+    MissingSprite {
+        file: String,
+        id: usize,
+        sprites: usize,
+    },
+    Shader {
+        file: String,
+        message: String,
+    },
+    Invalid {
+        file: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { file, source } => write!(f, "{file}: {source}"),
+            Self::Json { file, path, source } => write!(f, "{file}: at `{path}`: {source}"),
+            Self::Toml { file, source } => write!(f, "{file}: {source}"),
+            Self::MissingSprite { file, id, sprites } => {
+                write!(
+                    f,
+                    "{file}: sprite {id} is missing, the atlas has {sprites} sprites"
+                )
+            }
+            Self::Shader { file, message } | Self::Invalid { file, message } => {
+                write!(f, "{file}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<AssetError> for GameError {
+    fn from(e: AssetError) -> Self {
+        Self::ResourceLoadError(e.to_string())
+    }
+}
+
+/// Parses json from `reader`, on failure the error names `file` and the json
+/// path of the value that failed, e.g. `cards[12].powTop`.
+pub fn from_json_reader<T: DeserializeOwned>(
+    file: &str,
+    reader: impl Read,
+) -> Result<T, AssetError> {
+    let de = &mut serde_json::Deserializer::from_reader(reader);
+    serde_path_to_error::deserialize(de).map_err(|e| AssetError::Json {
+        file: file.into(),
+        path: e.path().to_string(),
+        source: e.into_inner(),
+    })
+}
@@ -0,0 +1,99 @@
+use crate::core::{DuelOutcome, Suit};
+use crate::graphics::ElementalEffect;
+use crate::profile::MatchSummary;
+use crate::utils::Sfx;
+
+/// How a card changed sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    Normal,
+    Same,
+    Plus,
+    /// Captured by a card that a Same or Plus flipped.
+    Combo,
+}
+
+/// Something that happened in a match, every subscriber sees all of them.
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    CardPlaced {
+        suit: Suit,
+        /// Key of the card, e.g. `base:12`.
+        card: String,
+        cell: usize,
+        effect: ElementalEffect,
+    },
+    CardCaptured {
+        cell: usize,
+        /// The side that captured it.
+        suit: Suit,
+        by: Capture,
+    },
+    /// A Combo step starts, the cards it captures follow.
+    ComboStep,
+    TurnStarted(Suit),
+    MatchEnded {
+        outcome: DuelOutcome,
+        sudden_death: bool,
+    },
+    /// The match as it goes to the player profile, once no Sudden Death
+    /// round follows.
+    MatchRecorded(Box<MatchSummary>),
+}
+
+impl GameEvent {
+    /// The sound the event makes, if any.
+    pub const fn sfx(&self) -> Option<Sfx> {
+        match self {
+            Self::CardPlaced { effect, .. } => Some(match effect {
+                ElementalEffect::Bonus => Sfx::ElementalBonus,
+                ElementalEffect::Malus => Sfx::ElementalMalus,
+                ElementalEffect::None => Sfx::Move,
+            }),
+            Self::CardCaptured {
+                by: Capture::Normal,
+                ..
+            } => Some(Sfx::Flip),
+            Self::CardCaptured { .. } => Some(Sfx::Combo),
+            Self::ComboStep
+            | Self::TurnStarted(_)
+            | Self::MatchEnded { .. }
+            | Self::MatchRecorded(_) => None,
+        }
+    }
+}
+
+/// Reacts to the events of a match. `MatchStats`, the audio and the
+/// achievements subscribe.
+pub trait Subscriber {
+    fn notify(&mut self, event: &GameEvent);
+}
+
+/// Queues the events published during a frame until they are dispatched.
+/// The board publishes `GameEvent`s, the screens publish `Event`s for the
+/// scene they run in.
+#[derive(Debug)]
+pub struct EventBus<E = GameEvent> {
+    queue: Vec<E>,
+}
+
+impl<E> Default for EventBus<E> {
+    fn default() -> Self {
+        Self { queue: Vec::new() }
+    }
+}
+
+impl<E> EventBus<E> {
+    pub fn publish(&mut self, event: E) {
+        self.queue.push(event);
+    }
+
+    /// Takes the queued events, oldest first.
+    pub fn drain(&mut self) -> Vec<E> {
+        std::mem::take(&mut self.queue)
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
@@ -85,7 +85,7 @@ impl MoveAnimation {
             target_pos.y,
             half_duration,
             Linear,
-            half_duration * -1.0,
+            -half_duration,
         );
         self.slide = Tweener::new_at(start_pos.x, target_pos.x, MOVE_TWEEN_DURATION, Linear, 0.0);
     }
@@ -394,7 +394,6 @@ pub struct CardEntity {
     pub id: usize,
     pub pos: Point2<f32>,
     pub controller: Suit,
-    pub owner: Suit,
     pub elemental_effect: ElementalEffect,
     scale: Point2<f32>,
     pub flipped: bool,
@@ -413,7 +412,6 @@ impl CardEntity {
         id: usize,
         pos: Point2<f32>,
         controller: Suit,
-        owner: Suit,
         flipped: bool,
         card_atlas: &Rc<CardAtlas>,
         sprite_sheet: &Rc<SpriteAtlas>,
@@ -447,7 +445,6 @@ impl CardEntity {
             id,
             pos,
             controller,
-            owner,
             scale: [1.0, 1.0].into(),
             flipped,
             elemental_effect: ElementalEffect::None,
@@ -486,14 +483,14 @@ impl CardEntity {
     }
 
     pub fn add_to_instance_array(&self, array: &mut InstanceArray) {
+        let card_stats = &self.card_atlas.cards[self.id];
         let rect_face = if self.flipped {
             self.sprite_sheet
                 .create_sprite(consts::CARD_BACK_SPRITE_ID)
                 .rect
         } else {
-            self.sprite_sheet.create_sprite(self.id).rect
+            self.sprite_sheet.create_sprite(card_stats.sprite_id).rect
         };
-        let card_stats = &self.card_atlas.cards[self.id];
         let rank_slice = card_stats.rank_as_slice();
 
         let card_pos = self.pos;
@@ -2,7 +2,9 @@ mod card_entity;
 mod element_entity;
 pub mod sprite;
 mod text_box;
+pub mod post;
 mod shape_3d;
+pub mod text;
 
 pub use card_entity::CardEntity;
 pub use card_entity::ElementalEffect;
@@ -0,0 +1,224 @@
+use crevice::std140::AsStd140;
+use ggez::graphics::{
+    Canvas, Color, DrawParam, Sampler, ScreenImage, Shader, ShaderBuilder, ShaderParams,
+    ShaderParamsBuilder,
+};
+use ggez::{Context, GameResult};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::assets::AssetSource;
+use crate::config::Video;
+use crate::error::AssetError;
+
+/// Seconds between two checks for modified shader files.
+const RELOAD_INTERVAL: f32 = 1.0;
+
+/// A full screen pass, see the shaders in the assets directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    Crt,
+    Scanlines,
+    Bloom,
+}
+
+impl Effect {
+    pub const ALL: [Self; 3] = [Self::Crt, Self::Scanlines, Self::Bloom];
+
+    pub const fn file(self) -> &'static str {
+        match self {
+            Self::Crt => "crt_shader.glsl",
+            Self::Scanlines => "scanlines_shader.glsl",
+            Self::Bloom => "bloom_shader.glsl",
+        }
+    }
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Crt => write!(f, "CRT"),
+            Self::Scanlines => write!(f, "Scanlines"),
+            Self::Bloom => write!(f, "Bloom"),
+        }
+    }
+}
+
+/// Parameters shared by every pass, must match `PostUniforms` in the shaders.
+#[derive(AsStd140)]
+pub struct PostUniforms {
+    screen_width: f32,
+    screen_height: f32,
+    intensity: f32,
+    curvature: f32,
+    crt_lines_x: f32,
+    crt_lines_y: f32,
+    scanlines: f32,
+    scanline_opacity: f32,
+    bloom_threshold: f32,
+    bloom_radius: f32,
+    bloom_strength: f32,
+}
+
+impl PostUniforms {
+    pub fn new(video: &Video, (screen_width, screen_height): (f32, f32)) -> Self {
+        Self {
+            screen_width,
+            screen_height,
+            intensity: video.intensity,
+            curvature: video.crt.curvature,
+            crt_lines_x: video.crt.resolution[0],
+            crt_lines_y: video.crt.resolution[1],
+            scanlines: video.scanlines.lines,
+            scanline_opacity: video.scanlines.opacity,
+            bloom_threshold: video.bloom.threshold,
+            bloom_radius: video.bloom.radius,
+            bloom_strength: video.bloom.strength,
+        }
+    }
+}
+
+struct Pass {
+    effect: Effect,
+    shader: Shader,
+    modified: Option<SystemTime>,
+}
+
+/// Draws the frame into an offscreen image and runs it through the enabled
+/// effects, each pass reads the output of the previous one.
+///
+/// When the assets come from a directory the shader files are watched and
+/// rebuilt as they change, a shader that doesn't compile is reported and the
+/// previous version stays in use.
+pub struct PostChain {
+    scene: ScreenImage,
+    targets: [ScreenImage; 2],
+    passes: Vec<Pass>,
+    params: ShaderParams<PostUniforms>,
+    reload_timer: f32,
+}
+
+impl PostChain {
+    pub fn new(ctx: &mut Context, assets: &AssetSource, video: &Video) -> GameResult<Self> {
+        let passes = Effect::ALL
+            .iter()
+            .map(|&effect| {
+                Ok(Pass {
+                    effect,
+                    shader: build_shader(ctx, assets, effect.file())?,
+                    modified: modified(assets, effect.file()),
+                })
+            })
+            .collect::<GameResult<_>>()?;
+        let uniforms = PostUniforms::new(video, ctx.gfx.drawable_size());
+
+        Ok(Self {
+            scene: ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            targets: [
+                ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+                ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            ],
+            passes,
+            params: ShaderParamsBuilder::new(&uniforms).build(ctx),
+            reload_timer: 0.0,
+        })
+    }
+
+    /// Rebuilds the shaders whose file changed, only when the assets come
+    /// from a directory.
+    pub fn update(&mut self, ctx: &Context, assets: &AssetSource) {
+        if !matches!(assets, AssetSource::Directory(_)) {
+            return;
+        }
+        self.reload_timer += ctx.time.delta().as_secs_f32();
+        if self.reload_timer < RELOAD_INTERVAL {
+            return;
+        }
+        self.reload_timer = 0.0;
+
+        for pass in &mut self.passes {
+            let modified = modified(assets, pass.effect.file());
+            if modified == pass.modified {
+                continue;
+            }
+            pass.modified = modified;
+            match build_shader(ctx, assets, pass.effect.file()) {
+                Ok(shader) => pass.shader = shader,
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+    }
+
+    /// The canvas the game is drawn on, the frame itself if no effect is on.
+    pub fn canvas(&mut self, ctx: &mut Context, video: &Video) -> Canvas {
+        if video.effects.is_empty() {
+            Canvas::from_frame(ctx, Color::BLACK)
+        } else {
+            Canvas::from_image(ctx, self.scene.image(ctx), Color::BLACK)
+        }
+    }
+
+    /// Runs the drawn scene through every enabled effect onto the frame.
+    pub fn apply(&mut self, ctx: &mut Context, video: &Video) -> GameResult {
+        if video.effects.is_empty() {
+            return Ok(());
+        }
+        let uniforms = PostUniforms::new(video, ctx.gfx.drawable_size());
+        self.params.set_uniforms(ctx, &uniforms);
+
+        let mut source = self.scene.image(ctx);
+        for (i, effect) in video.effects.iter().enumerate() {
+            let last = i + 1 == video.effects.len();
+            let target = (!last).then(|| self.targets[i % 2].image(ctx));
+            let mut canvas = match &target {
+                Some(image) => Canvas::from_image(ctx, image.clone(), Color::BLACK),
+                None => Canvas::from_frame(ctx, Color::BLACK),
+            };
+            let pass = self
+                .passes
+                .iter()
+                .find(|pass| pass.effect == *effect)
+                .expect("Every effect has a pass");
+            canvas.set_shader(&pass.shader);
+            canvas.set_shader_params(&self.params);
+            canvas.set_sampler(Sampler::nearest_clamp());
+            canvas.draw(&source, DrawParam::default());
+            canvas.finish(ctx)?;
+
+            if let Some(image) = target {
+                source = image;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the shader with naga before handing it to wgpu, which panics on
+/// invalid code.
+fn build_shader(ctx: &Context, assets: &AssetSource, file: &str) -> GameResult<Shader> {
+    let code = assets.read_to_string(file)?;
+    let shader_error = |message| AssetError::Shader {
+        file: file.into(),
+        message,
+    };
+    let module =
+        naga::front::wgsl::parse_str(&code).map_err(|e| shader_error(e.emit_to_string(&code)))?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|e| shader_error(e.into_inner().to_string()))?;
+
+    ShaderBuilder::new().fragment_code(&code).build(&ctx.gfx)
+}
+
+fn modified(assets: &AssetSource, file: &str) -> Option<SystemTime> {
+    match assets {
+        AssetSource::Directory(dir) => std::fs::metadata(dir.join(file)).ok()?.modified().ok(),
+        AssetSource::Embedded => None,
+    }
+}
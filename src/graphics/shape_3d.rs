@@ -9,6 +9,8 @@ use std::f32;
 use std::path;
 use wgpu::util::DeviceExt;
 
+use crate::layout::Layout;
+
 type Isometry3 = Mat4;
 type Point3 = Vec3;
 type Vector3 = Vec3;
@@ -283,6 +285,7 @@ impl Shape {
             .write_buffer(&self.locals, 0, locals.as_std140().as_bytes());
 
         let depth = self.depth.image(ctx);
+        let viewport = Layout::from_ctx(ctx).viewport;
 
         let frame = ctx.gfx.frame().clone();
         let cmd = ctx.gfx.commands().unwrap();
@@ -305,6 +308,7 @@ impl Shape {
                 stencil_ops: None,
             }),
         });
+        pass.set_viewport(viewport.x, viewport.y, viewport.w, viewport.h, 0.0, 1.0);
         pass.set_blend_constant(wgpu::Color::TRANSPARENT);
         pass.set_pipeline(&self.pipeline);
         pass.set_bind_group(0, &self.bind_group, &[]);
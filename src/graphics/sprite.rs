@@ -3,6 +3,9 @@ use mint::{Point2, Vector2};
 use serde::Deserialize;
 use serde_with::serde_as;
 
+use crate::assets::AssetSource;
+use crate::error::AssetError;
+
 #[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 struct Meta {
@@ -63,7 +66,6 @@ struct JsonRect {
     w: i16,
     h: i16,
 }
-#[allow(dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 struct SpriteData {
     id: usize,
@@ -78,13 +80,61 @@ pub struct Atlas {
     meta: Meta,
 }
 impl Atlas {
-    pub fn parse_atlas_json(filename: &str) -> Self {
-        use std::fs::File;
-        use std::io::BufReader;
-        let path = std::path::PathBuf::from(filename);
-        let file = File::open(path).expect("Couldn't find the texture_atlas file");
-        let buf_reader = BufReader::new(file);
-        serde_json::from_reader(buf_reader).expect("Couldn't create texture atlas")
+    pub fn parse_atlas_json(assets: &AssetSource, filename: &str) -> Result<Self, AssetError> {
+        assets.json(filename)
+    }
+
+    /// Makes sure every sprite in `ids` exists, so `create_sprite` can't miss.
+    pub fn check_sprites(
+        &self,
+        filename: &str,
+        ids: impl IntoIterator<Item = usize>,
+    ) -> Result<(), AssetError> {
+        match ids.into_iter().find(|id| *id >= self.sprites.len()) {
+            Some(id) => Err(AssetError::MissingSprite {
+                file: filename.into(),
+                id,
+                sprites: self.sprites.len(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of sprites in the Atlas.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Returns the index of the sprite with the given json `id`.
+    pub fn position(&self, id: usize) -> Option<usize> {
+        self.sprites.iter().position(|sprite| sprite.id == id)
+    }
+
+    /// The size of the sheet as given in the json, width then height.
+    pub fn size(&self) -> (u32, u32) {
+        let dimension = |value: i16| u32::try_from(value).unwrap_or(0);
+        (dimension(self.meta.size.w), dimension(self.meta.size.h))
+    }
+
+    /// Appends the sprites of `other`, whose sheet is stacked below this one.
+    /// Returns the index of the first appended sprite, or `None` and leaves
+    /// the atlas as it is if the stacked sheet is too tall for the frames.
+    pub fn append(&mut self, other: Self) -> Option<usize> {
+        let first = self.sprites.len();
+        let y_offset = self.meta.size.h;
+        let height = y_offset.checked_add(other.meta.size.h)?;
+        self.sprites
+            .extend(other.sprites.into_iter().map(|mut sprite| {
+                sprite.frame.y += y_offset;
+                sprite
+            }));
+        self.meta.size.w = self.meta.size.w.max(other.meta.size.w);
+        self.meta.size.h = height;
+        Some(first)
     }
 
     /// Returns a sprite from the Atlas.
@@ -93,7 +143,9 @@ impl Atlas {
         let height = f32::from(self.meta.size.h);
         let atlas_rect = graphics::Rect::new(0.0, 0.0, width, height);
 
-        let sprite_data = &self.sprites[id];
+        let Some(sprite_data) = self.sprites.get(id) else {
+            return Sprite::new(graphics::Rect::zero(), 0.0, 0.0);
+        };
 
         Sprite::new(
             graphics::Rect::fraction(
@@ -0,0 +1,29 @@
+use ggez::graphics::{Canvas, Color, PxScale, Text, TextFragment, TextLayout};
+
+use crate::consts::FONT_SIZE;
+
+/// Draws `text` in white with its shadow, from its top left corner.
+pub fn draw_label(canvas: &mut Canvas, text: &str, pos: [f32; 2]) {
+    draw_text(canvas, text, pos, Color::WHITE, TextLayout::top_left());
+}
+
+/// Draws `text` in `color` with its shadow, `layout` places it around `pos`.
+pub fn draw_text(canvas: &mut Canvas, text: &str, pos: [f32; 2], color: Color, layout: TextLayout) {
+    let fragment = |color| TextFragment {
+        text: text.into(),
+        color: Some(color),
+        font: Some("pixel font".into()),
+        scale: Some(PxScale::from(FONT_SIZE)),
+    };
+    let mut label = Text::new(fragment(color));
+    let mut shadow = Text::new(fragment(Color::from_rgb(50, 50, 50)));
+    label.set_layout(layout);
+    shadow.set_layout(layout);
+    canvas.draw(&shadow, [pos[0] + 2.0, pos[1] + 2.0]);
+    canvas.draw(&label, pos);
+}
+
+/// The index of a row or a column, to lay it out with `mul_add`.
+pub fn row(i: usize) -> f32 {
+    f32::from(u8::try_from(i).expect("Value is too big"))
+}
@@ -0,0 +1,193 @@
+use ggez::graphics::Rect;
+use ggez::Context;
+use mint::Point2;
+
+use crate::consts::WINDOW_DIMENSIONS;
+
+pub const MIN_WINDOW_DIMENSIONS: [f32; 2] = [400.0, 300.0];
+
+/// Maps the 800×600 space the game is laid out in onto the actual window.
+///
+/// The play area keeps its aspect ratio and is centered, the bars left on the
+/// sides (or above and below) are part of the screen coordinates so nothing
+/// drawn there gets stretched. Pure math, so it can be used without a GPU.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// Window pixels per layout unit.
+    pub scale: f32,
+    /// The whole window in layout units, the play area is `(0, 0, 800, 600)`.
+    pub screen: Rect,
+    /// The play area in window pixels.
+    pub viewport: Rect,
+}
+
+impl Layout {
+    pub fn new(window_width: f32, window_height: f32) -> Self {
+        let [width, height] = WINDOW_DIMENSIONS;
+        let window_width = window_width.max(1.0);
+        let window_height = window_height.max(1.0);
+
+        let scale = (window_width / width).min(window_height / height);
+        let screen_width = window_width / scale;
+        let screen_height = window_height / scale;
+        let screen = Rect::new(
+            (width - screen_width) / 2.0,
+            (height - screen_height) / 2.0,
+            screen_width,
+            screen_height,
+        );
+        let viewport = Rect::new(
+            -screen.x * scale,
+            -screen.y * scale,
+            width * scale,
+            height * scale,
+        );
+
+        Self {
+            scale,
+            screen,
+            viewport,
+        }
+    }
+
+    /// Layout of the current window.
+    pub fn from_ctx(ctx: &Context) -> Self {
+        let (width, height) = ctx.gfx.drawable_size();
+        Self::new(width, height)
+    }
+
+    /// Converts a point in window pixels to layout units.
+    pub fn to_layout(&self, point: Point2<f32>) -> Point2<f32> {
+        Point2 {
+            x: (point.x - self.viewport.x) / self.scale,
+            y: (point.y - self.viewport.y) / self.scale,
+        }
+    }
+
+    /// Converts a rect in layout units to window pixels.
+    pub fn to_window(&self, rect: Rect) -> Rect {
+        Rect::new(
+            rect.x.mul_add(self.scale, self.viewport.x),
+            rect.y.mul_add(self.scale, self.viewport.y),
+            rect.w * self.scale,
+            rect.h * self.scale,
+        )
+    }
+}
+
+/// Mouse position in layout units, use it instead of `ctx.mouse.position()`.
+pub fn mouse_position(ctx: &Context) -> Point2<f32> {
+    Layout::from_ctx(ctx).to_layout(ctx.mouse.position())
+}
+
+/// Parses a window size such as `1024x768`.
+pub fn parse_size(size: &str) -> Option<[f32; 2]> {
+    let (width, height) = size.split_once('x')?;
+    let width: u16 = width.trim().parse().ok()?;
+    let height: u16 = height.trim().parse().ok()?;
+    let size = [f32::from(width), f32::from(height)];
+    (size[0] >= MIN_WINDOW_DIMENSIONS[0] && size[1] >= MIN_WINDOW_DIMENSIONS[1]).then_some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32) -> Point2<f32> {
+        Point2 { x, y }
+    }
+
+    fn assert_close(left: Rect, right: Rect) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(
+            close(left.x, right.x)
+                && close(left.y, right.y)
+                && close(left.w, right.w)
+                && close(left.h, right.h),
+            "{left:?} != {right:?}"
+        );
+    }
+
+    #[test]
+    fn same_size_window() {
+        let layout = Layout::new(800.0, 600.0);
+        assert_eq!(layout.scale, 1.0);
+        assert_eq!(layout.screen, Rect::new(0.0, 0.0, 800.0, 600.0));
+        assert_eq!(layout.viewport, Rect::new(0.0, 0.0, 800.0, 600.0));
+    }
+
+    #[test]
+    fn wider_window_has_bars_on_the_sides() {
+        let layout = Layout::new(1600.0, 900.0);
+        assert_eq!(layout.scale, 1.5);
+        assert_close(layout.viewport, Rect::new(200.0, 0.0, 1200.0, 900.0));
+        assert_close(
+            layout.screen,
+            Rect::new(-400.0 / 3.0, 0.0, 3200.0 / 3.0, 600.0),
+        );
+    }
+
+    #[test]
+    fn taller_window_has_bars_above_and_below() {
+        let layout = Layout::new(800.0, 1000.0);
+        assert_eq!(layout.scale, 1.0);
+        assert_close(layout.viewport, Rect::new(0.0, 200.0, 800.0, 600.0));
+        assert_close(layout.screen, Rect::new(0.0, -200.0, 800.0, 1000.0));
+    }
+
+    #[test]
+    fn window_and_layout_round_trip() {
+        let layout = Layout::new(1600.0, 900.0);
+        let rect = Rect::new(100.0, 50.0, 40.0, 20.0);
+        let window = layout.to_window(rect);
+        assert_close(window, Rect::new(350.0, 75.0, 60.0, 30.0));
+        let back = layout.to_layout(window.point());
+        assert_close(
+            Rect::new(back.x, back.y, 0.0, 0.0),
+            Rect::new(100.0, 50.0, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn mouse_over_the_bars_is_outside_the_play_area() {
+        let layout = Layout::new(1600.0, 900.0);
+        let play_area = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let left = layout.to_layout(point(100.0, 450.0));
+        let right = layout.to_layout(point(1500.0, 450.0));
+        assert!(left.x < 0.0 && !play_area.contains(left));
+        assert!(right.x > 800.0 && !play_area.contains(right));
+        assert!(layout.screen.contains(left) && layout.screen.contains(right));
+    }
+
+    #[test]
+    fn degenerate_window() {
+        let layout = Layout::new(0.0, 0.0);
+        assert!(layout.scale > 0.0 && layout.scale.is_finite());
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(parse_size("1024x768"), Some([1024.0, 768.0]));
+        assert_eq!(parse_size(" 800 x 600 "), Some([800.0, 600.0]));
+        assert_eq!(parse_size("400x300"), Some(MIN_WINDOW_DIMENSIONS));
+    }
+
+    #[test]
+    fn parse_size_errors() {
+        for size in [
+            "",
+            "1024",
+            "1024x",
+            "x768",
+            "1024*768",
+            "axb",
+            "-800x600",
+            "800x600x2",
+            "99999x600",
+            "399x300",
+            "400x299",
+        ] {
+            assert_eq!(parse_size(size), None, "{size}");
+        }
+    }
+}
@@ -0,0 +1,21 @@
+pub mod achievements;
+pub mod app;
+pub mod assets;
+pub mod audio;
+pub mod campaign;
+pub mod collection;
+pub mod config;
+pub mod consts;
+pub mod core;
+pub mod deck;
+pub mod error;
+pub mod events;
+pub mod graphics;
+pub mod layout;
+pub mod mods;
+pub mod opponents;
+pub mod persist;
+pub mod profile;
+pub mod state;
+pub mod utils;
+pub mod validate;
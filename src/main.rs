@@ -1,57 +1,81 @@
-use app::SoundManager;
 use ggez::{
+    conf::{FullscreenType, WindowMode},
     event,
-    audio,
-    graphics::{Canvas, Color, FontData, Image, InstanceArray, Rect, Sampler},
+    graphics::{InstanceArray, Sampler},
+    input::keyboard::KeyCode,
     Context, GameResult,
 };
 
 use std::rc::Rc;
 
-use crate::app::App;
-use crate::core::CardAtlas;
-use crate::graphics::sprite::Atlas as SpriteAtlas;
-
-mod app;
-mod consts;
-mod core;
-mod graphics;
-mod state;
-mod utils;
-
-const CARD_SPRITESHEET_FILE: &str = "/card_sprites.png";
-const ELEM_SPRITESHEET_FILE: &str = "/elem_animations.png";
-const CARD_SPRITESHEET_DATA: &str = "./assets/card_sprites.json";
-const CARD_ATLAS_JSON: &str = "./assets/cards.json";
-const FF8_FONT: &str = "/seed-computer.ttf";
-const BOARD: &str = "/board.png";
-
-const SOUND_BGM: &str = "/sfx/src_assets_sounds_music.mp3";
-const SOUND_FLIP: &str = "/sfx/src_assets_sounds_flip_card.mp3";
-const SOUND_MOVE: &str = "/sfx/src_assets_sounds_move_card.mp3";
-const SOUND_SELECT: &str = "/sfx/src_assets_sounds_select.mp3";
-const SOUND_CANCEL: &str = "/sfx/src_assets_sounds_cancel.mp3";
-const SOUND_VICORY: &str = "/sfx/src_assets_sounds_victory.mp3";
+use triple_triad::achievements::Achievements;
+use triple_triad::app::App;
+use triple_triad::assets::AssetSource;
+use triple_triad::audio::AudioManager;
+use triple_triad::campaign::Campaign;
+use triple_triad::consts;
+use triple_triad::core::CardAtlas;
+use triple_triad::graphics::post::PostChain;
+use triple_triad::graphics::sprite::Atlas as SpriteAtlas;
+use triple_triad::layout::{self, Layout};
+use triple_triad::mods;
+use triple_triad::opponents::Roster;
+use triple_triad::state::ErrorScreen;
+
+const CARD_SPRITESHEET_FILE: &str = "card_sprites.png";
+const ELEM_SPRITESHEET_FILE: &str = "elem_animations.png";
+const CARD_SPRITESHEET_DATA: &str = "card_sprites.json";
+const CARD_ATLAS_JSON: &str = "cards.json";
+const FF8_FONT: &str = "seed-computer.ttf";
+const BOARD: &str = "board.png";
 
 //const TRADE: &str = "/trade.png";
 
 struct MainState {
     app: App,
-    shader: ggez::graphics::Shader,
+    post: PostChain,
+    assets: AssetSource,
+    fullscreen: bool,
     _card_atlas: Rc<CardAtlas>,
     _card_sprite_sheet: Rc<SpriteAtlas>,
 }
 
 impl MainState {
-    fn new(
-        ctx: &mut Context,
-        card_atlas: CardAtlas,
-        card_sprite_sheet: SpriteAtlas,
-        board_bg: Image,
-        card_instance_array: InstanceArray,
-        elem_instance_array: InstanceArray,
-        sound_manager: SoundManager,
-    ) -> GameResult<Self> {
+    fn new(ctx: &mut Context, assets: &AssetSource) -> GameResult<Self> {
+        let audio = AudioManager::load(assets)?;
+
+        let elem_instance_array = create_sprite_instance_array(ctx, assets, ELEM_SPRITESHEET_FILE)?;
+        let mut card_atlas = CardAtlas::parse_atlas_json(assets, CARD_ATLAS_JSON)?;
+        let mut card_sprite_sheet = SpriteAtlas::parse_atlas_json(assets, CARD_SPRITESHEET_DATA)?;
+
+        let (packs, mut conflicts) = mods::load_packs(&ctx.fs.user_data_dir().join(mods::MODS_DIR));
+        let card_image = mods::install(
+            ctx,
+            packs,
+            &mut card_atlas,
+            &mut card_sprite_sheet,
+            assets.image(ctx, CARD_SPRITESHEET_FILE)?,
+            &mut conflicts,
+        )?;
+        for conflict in &conflicts {
+            eprintln!("Card pack: {conflict}");
+        }
+        card_sprite_sheet.check_sprites(
+            CARD_SPRITESHEET_DATA,
+            card_atlas
+                .cards
+                .iter()
+                .map(|card| card.sprite_id)
+                .chain(consts::CARD_BACK_SPRITE_ID..=consts::RIGHT_ARROW_SPRITE_ID),
+        )?;
+        let campaign = Campaign::load(assets, &card_atlas)?;
+        let roster = Roster::load(assets, &card_atlas)?;
+        let achievements = Achievements::load(assets)?;
+        let card_instance_array = InstanceArray::new(ctx, card_image);
+        let board_bg = assets.image(ctx, BOARD)?;
+
+        ctx.gfx.add_font("pixel font", assets.font(FF8_FONT)?);
+
         let card_atlas = Rc::new(card_atlas);
         let card_sprite_sheet = Rc::new(card_sprite_sheet);
         let board_bg = Rc::new(board_bg);
@@ -62,90 +86,125 @@ impl MainState {
             &board_bg,
             card_instance_array,
             elem_instance_array,
-            sound_manager
+            audio,
+            campaign,
+            roster,
+            achievements,
         );
-        let shader = ggez::graphics::ShaderBuilder::new()
-            .fragment_path("/crt_shader.glsl")
-            .build(&ctx.gfx)?;
+        let post = PostChain::new(ctx, assets, &app.config().video)?;
 
         Ok(Self {
             _card_atlas: Rc::clone(&card_atlas),
             _card_sprite_sheet: Rc::clone(&card_sprite_sheet),
             app,
-            shader
+            post,
+            assets: assets.clone(),
+            fullscreen: false,
         })
     }
 }
 
 impl event::EventHandler<ggez::GameError> for MainState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if ctx.keyboard.is_key_just_pressed(KeyCode::F11) {
+            self.fullscreen = !self.fullscreen;
+            ctx.gfx.set_fullscreen(fullscreen_type(self.fullscreen))?;
+        }
+        self.post.update(ctx, &self.assets);
         self.app.update(ctx)?;
         Ok(())
     }
 
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        self.app.text_input(character);
+        Ok(())
+    }
+
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let mut canvas = Canvas::from_frame(ctx, Color::from([0.1, 0.2, 0.3, 1.0]));
-        canvas.set_shader(&self.shader.clone());
+        let mut canvas = self.post.canvas(ctx, &self.app.config().video);
         canvas.set_sampler(Sampler::nearest_clamp());
-        canvas.set_screen_coordinates(Rect {
-            x: 0.0,
-            y: 0.0,
-            w: 800.0,
-            h: 600.0,
-        });
+        canvas.set_screen_coordinates(Layout::from_ctx(ctx).screen);
 
         self.app.draw(ctx, &mut canvas);
         canvas.finish(ctx)?;
+        self.post.apply(ctx, &self.app.config().video)?;
         self.app.wgpu_shapes.draw(ctx);
         Ok(())
     }
 }
 
+struct Options {
+    fullscreen: bool,
+    size: [f32; 2],
+}
+
+/// Reads `--fullscreen` and `--size <WIDTH>x<HEIGHT>` from the command line.
+fn parse_args() -> Options {
+    let mut options = Options {
+        fullscreen: false,
+        size: consts::WINDOW_DIMENSIONS,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fullscreen" => options.fullscreen = true,
+            "--size" => match args.next().as_deref().and_then(layout::parse_size) {
+                Some(size) => options.size = size,
+                None => eprintln!(
+                    "--size expects <WIDTH>x<HEIGHT>, at least {}x{}",
+                    layout::MIN_WINDOW_DIMENSIONS[0],
+                    layout::MIN_WINDOW_DIMENSIONS[1]
+                ),
+            },
+            _ => eprintln!("Unknown argument: {arg}"),
+        }
+    }
+    options
+}
+
+const fn fullscreen_type(fullscreen: bool) -> FullscreenType {
+    if fullscreen {
+        FullscreenType::Desktop
+    } else {
+        FullscreenType::Windowed
+    }
+}
+
 pub fn main() -> GameResult {
-    let resource_dir = std::path::PathBuf::from("./assets");
-    let cb = ggez::ContextBuilder::new("Triple Triad", "ggez")
-        .window_mode(ggez::conf::WindowMode::default().transparent(true).resizable(false))
-        .add_resource_path(resource_dir);
+    let options = parse_args();
+    let cb = ggez::ContextBuilder::new("Triple Triad", "ggez").window_mode(
+        WindowMode::default()
+            .transparent(true)
+            .resizable(true)
+            .dimensions(options.size[0], options.size[1])
+            .min_dimensions(
+                layout::MIN_WINDOW_DIMENSIONS[0],
+                layout::MIN_WINDOW_DIMENSIONS[1],
+            )
+            .fullscreen_type(fullscreen_type(options.fullscreen)),
+    );
     let (mut ctx, event_loop) = cb.build()?;
-    let bgm = audio::Source::new(&ctx, SOUND_BGM)?;
-    let flip_card = audio::Source::new(&ctx, SOUND_FLIP)?;
-    let move_card = audio::Source::new(&ctx, SOUND_MOVE)?;
-    let select = audio::Source::new(&ctx, SOUND_SELECT)?;
-    let cancel = audio::Source::new(&ctx, SOUND_CANCEL)?;
-    let victory = audio::Source::new(&ctx, SOUND_VICORY)?;
-    
-    let sound_manager = SoundManager{
-        bgm,
-        flip_card,
-        move_card,
-        select,
-        cancel,
-        victory
-    };
 
-    let card_instance_array = create_sprite_instance_array(&mut ctx, CARD_SPRITESHEET_FILE);
-    let elem_instance_array = create_sprite_instance_array(&mut ctx, ELEM_SPRITESHEET_FILE);
-    let card_atlas = CardAtlas::parse_atlas_json(CARD_ATLAS_JSON);
-    let card_sprite_sheet = SpriteAtlas::parse_atlas_json(CARD_SPRITESHEET_DATA);
-    let board_bg = Image::from_path(&ctx, BOARD)?;
-
-    let state = MainState::new(
-        &mut ctx,
-        card_atlas,
-        card_sprite_sheet,
-        board_bg,
-        card_instance_array,
-        elem_instance_array,
-        sound_manager
-    )?;
-
-    ctx.gfx
-        .add_font("pixel font", FontData::from_path(&ctx, FF8_FONT)?);
-    event::run(ctx, event_loop, state)
+    let assets = AssetSource::from_env();
+
+    match MainState::new(&mut ctx, &assets) {
+        Ok(mut state) => {
+            state.fullscreen = options.fullscreen;
+            event::run(ctx, event_loop, state)
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            event::run(ctx, event_loop, ErrorScreen::new(&e))
+        }
+    }
 }
 
-fn create_sprite_instance_array(ctx: &mut Context, filename: &str) -> InstanceArray {
-    let image = Image::from_path(ctx, filename).expect("Error while opening a file {filename}");
+fn create_sprite_instance_array(
+    ctx: &mut Context,
+    assets: &AssetSource,
+    filename: &str,
+) -> GameResult<InstanceArray> {
+    let image = assets.image(ctx, filename)?;
 
-    InstanceArray::new(ctx, image)
+    Ok(InstanceArray::new(ctx, image))
 }
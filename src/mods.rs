@@ -0,0 +1,252 @@
+use ggez::graphics::{Image, ImageFormat};
+use ggez::{Context, GameResult};
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::core::{CardAtlas, BASE_PACK};
+use crate::error::{self, AssetError};
+use crate::graphics::sprite::Atlas as SpriteAtlas;
+
+pub const MODS_DIR: &str = "mods";
+
+pub const PACK_CARDS_FILE: &str = "cards.json";
+pub const PACK_SPRITES_DATA: &str = "card_sprites.json";
+const PACK_SPRITES_FILE: &str = "card_sprites.png";
+
+/// A card pack found in the mods directory.
+pub struct CardPack {
+    pub name: String,
+    cards: CardAtlas,
+    sprites: SpriteAtlas,
+    image: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum PackConflict {
+    Unreadable {
+        pack: String,
+        reason: String,
+    },
+    ReservedName {
+        pack: String,
+    },
+    DuplicateId {
+        pack: String,
+        id: usize,
+    },
+    MissingSprite {
+        pack: String,
+        id: usize,
+    },
+    DuplicateName {
+        key: String,
+        other: String,
+        name: String,
+    },
+}
+
+impl fmt::Display for PackConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable { pack, reason } => {
+                write!(f, "pack `{pack}` skipped: {reason}")
+            }
+            Self::ReservedName { pack } => {
+                write!(f, "pack `{pack}` skipped: the name is reserved")
+            }
+            Self::DuplicateId { pack, id } => {
+                write!(
+                    f,
+                    "card `{pack}:{id}` skipped: the id is already used in the pack"
+                )
+            }
+            Self::MissingSprite { pack, id } => {
+                write!(f, "card `{pack}:{id}` skipped: no sprite with this id")
+            }
+            Self::DuplicateName { key, other, name } => {
+                write!(f, "card `{key}` has the same name as `{other}`: {name}")
+            }
+        }
+    }
+}
+
+impl CardPack {
+    fn load(dir: &Path) -> Result<Self, PackConflict> {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let unreadable = |reason: String| PackConflict::Unreadable {
+            pack: name.clone(),
+            reason,
+        };
+
+        if name == BASE_PACK {
+            return Err(PackConflict::ReservedName { pack: name });
+        }
+
+        let cards = read_json(&dir.join(PACK_CARDS_FILE)).map_err(unreadable)?;
+        let sprites = read_json(&dir.join(PACK_SPRITES_DATA)).map_err(unreadable)?;
+        let image = std::fs::read(dir.join(PACK_SPRITES_FILE))
+            .map_err(|e| unreadable(format!("{PACK_SPRITES_FILE}: {e}")))?;
+
+        Ok(Self {
+            name,
+            cards,
+            sprites,
+            image,
+        })
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let file = std::fs::File::open(path).map_err(|e| format!("{file_name}: {e}"))?;
+    error::from_json_reader(&file_name, std::io::BufReader::new(file)).map_err(|e| e.to_string())
+}
+
+/// Loads every pack directory inside `dir`, sorted by name.
+pub fn load_packs(dir: &Path) -> (Vec<CardPack>, Vec<PackConflict>) {
+    let mut packs = Vec::new();
+    let mut conflicts = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (packs, conflicts);
+    };
+
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    for dir in dirs {
+        match CardPack::load(&dir) {
+            Ok(pack) => packs.push(pack),
+            Err(conflict) => conflicts.push(conflict),
+        }
+    }
+
+    (packs, conflicts)
+}
+
+/// Merges the packs into the card atlas and the sprite atlas. The pack sprite
+/// sheets are stacked below `image`, the combined image is returned.
+pub fn install(
+    ctx: &Context,
+    packs: Vec<CardPack>,
+    card_atlas: &mut CardAtlas,
+    sprite_sheet: &mut SpriteAtlas,
+    image: Image,
+    conflicts: &mut Vec<PackConflict>,
+) -> GameResult<Image> {
+    if packs.is_empty() {
+        return Ok(image);
+    }
+
+    // The pack frames are offset by the height given in the json.
+    let size = (image.width(), image.height());
+    if sprite_sheet.size() != size {
+        return Err(AssetError::Invalid {
+            file: PACK_SPRITES_DATA.into(),
+            message: sheet_mismatch(sprite_sheet.size(), size),
+        }
+        .into());
+    }
+
+    let mut sheets = vec![(image.width(), image.height(), image.to_pixels(ctx)?)];
+
+    for pack in packs {
+        let pack_image = Image::from_bytes(ctx, &pack.image)?;
+        let pack_sprites = pack.sprites;
+        let size = (pack_image.width(), pack_image.height());
+        if pack_sprites.size() != size {
+            conflicts.push(PackConflict::Unreadable {
+                pack: pack.name,
+                reason: sheet_mismatch(pack_sprites.size(), size),
+            });
+            continue;
+        }
+        let first_sprite = sprite_sheet.len();
+        let mut ids = HashSet::new();
+        let mut cards = Vec::with_capacity(pack.cards.cards.len());
+
+        for mut card in pack.cards.cards {
+            if !ids.insert(card.id) {
+                conflicts.push(PackConflict::DuplicateId {
+                    pack: pack.name.clone(),
+                    id: card.id,
+                });
+                continue;
+            }
+            let Some(sprite) = pack_sprites.position(card.id) else {
+                conflicts.push(PackConflict::MissingSprite {
+                    pack: pack.name.clone(),
+                    id: card.id,
+                });
+                continue;
+            };
+            card.sprite_id = first_sprite + sprite;
+            card.pack = pack.name.clone();
+
+            if let Some(other) = card_atlas
+                .cards
+                .iter()
+                .chain(&cards)
+                .find(|c| c.name == card.name)
+            {
+                conflicts.push(PackConflict::DuplicateName {
+                    key: card.key(),
+                    other: other.key(),
+                    name: card.name.clone(),
+                });
+            }
+            cards.push(card);
+        }
+
+        if sprite_sheet.append(pack_sprites).is_none() {
+            conflicts.push(PackConflict::Unreadable {
+                pack: pack.name,
+                reason: "the sprite sheets of the packs are too tall together".into(),
+            });
+            continue;
+        }
+        card_atlas.cards.extend(cards);
+        sheets.push((
+            pack_image.width(),
+            pack_image.height(),
+            pack_image.to_pixels(ctx)?,
+        ));
+    }
+
+    Ok(stack_sheets(ctx, image.format(), &sheets))
+}
+
+fn sheet_mismatch(json: (u32, u32), image: (u32, u32)) -> String {
+    format!(
+        "{PACK_SPRITES_DATA} gives a {}x{} sheet but {PACK_SPRITES_FILE} is {}x{}",
+        json.0, json.1, image.0, image.1
+    )
+}
+
+fn stack_sheets(ctx: &Context, format: ImageFormat, sheets: &[(u32, u32, Vec<u8>)]) -> Image {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    let width = sheets.iter().map(|(w, _, _)| *w).max().unwrap_or(0);
+    let height = sheets.iter().map(|(_, h, _)| *h).sum();
+    let row_len = width as usize * BYTES_PER_PIXEL;
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+
+    for (w, _, sheet) in sheets {
+        let sheet_row_len = *w as usize * BYTES_PER_PIXEL;
+        for row in sheet.chunks_exact(sheet_row_len) {
+            pixels.extend_from_slice(row);
+            pixels.resize(pixels.len() + row_len - sheet_row_len, 0);
+        }
+    }
+
+    Image::from_pixels(ctx, &pixels, format, width, height)
+}
@@ -0,0 +1,350 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use std::collections::HashMap;
+
+use crate::assets::AssetSource;
+use crate::core::CardAtlas;
+use crate::deck::DECK_SIZE;
+use crate::error::AssetError;
+
+pub const OPPONENTS_FILE: &str = "opponents.json";
+
+/// One entry of a draw pool, either a single card or every card of a level.
+/// `weight` is relative to the other entries of the pool.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Draw {
+    Card { card: String, weight: u32 },
+    Level { level: u8, weight: u32 },
+}
+
+/// How an opponent's hand is dealt: the held cards always come up, the rest
+/// is drawn from the pool. Cards are keyed by `pack:id`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DeckSpec {
+    pub holds: Vec<String>,
+    pub pool: Vec<Draw>,
+    /// No card can be dealt twice.
+    pub unique: bool,
+}
+
+impl DeckSpec {
+    /// Checks that the deck names known cards and can always deal a full
+    /// hand, the error explains what is wrong.
+    pub fn check(&self, card_atlas: &CardAtlas) -> Result<(), String> {
+        if let Some(key) = self
+            .holds
+            .iter()
+            .chain(self.pool.iter().filter_map(|draw| match draw {
+                Draw::Card { card, .. } => Some(card),
+                Draw::Level { .. } => None,
+            }))
+            .find(|key| card_atlas.position(key).is_none())
+        {
+            return Err(format!("unknown card `{key}`"));
+        }
+        if let Some(level) = self.pool.iter().find_map(|draw| match draw {
+            Draw::Level { level, .. } if !card_atlas.levels().contains(level) => Some(level),
+            _ => None,
+        }) {
+            return Err(format!("level {level} has no cards"));
+        }
+        if self.holds.len() > DECK_SIZE {
+            return Err(format!("more than {DECK_SIZE} cards are held"));
+        }
+
+        let held = self.held(card_atlas);
+        let candidates = self.candidates(card_atlas);
+        if self.unique {
+            if held.windows(2).any(|pair| pair[0] == pair[1]) {
+                return Err("a unique deck holds a card twice".into());
+            }
+            let mut ids: Vec<usize> = candidates
+                .iter()
+                .map(|(id, _)| *id)
+                .filter(|id| !held.contains(id))
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+            if held.len() + ids.len() < DECK_SIZE {
+                return Err(format!("the deck can't deal {DECK_SIZE} different cards"));
+            }
+        } else if held.len() < DECK_SIZE && candidates.is_empty() {
+            return Err(format!("the deck can't deal {DECK_SIZE} cards"));
+        }
+        Ok(())
+    }
+
+    /// The atlas index of every held card, sorted.
+    fn held(&self, card_atlas: &CardAtlas) -> Vec<usize> {
+        let mut held: Vec<usize> = self
+            .holds
+            .iter()
+            .filter_map(|key| card_atlas.position(key))
+            .collect();
+        held.sort_unstable();
+        held
+    }
+
+    /// Every card the pool can draw with its weight.
+    fn candidates(&self, card_atlas: &CardAtlas) -> Vec<(usize, u32)> {
+        self.pool
+            .iter()
+            .flat_map(|draw| -> Vec<(usize, u32)> {
+                match draw {
+                    Draw::Card { card, weight } => card_atlas
+                        .position(card)
+                        .map(|id| (id, *weight))
+                        .into_iter()
+                        .collect(),
+                    Draw::Level { level, weight } => card_atlas
+                        .cards
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, card)| card.level == *level)
+                        .map(|(id, _)| (id, *weight))
+                        .collect(),
+                }
+            })
+            .filter(|(_, weight)| *weight > 0)
+            .collect()
+    }
+
+    /// Deals a hand, the held cards end up in random slots. The deck must
+    /// have passed `check`.
+    pub fn deal(&self, card_atlas: &CardAtlas) -> [usize; DECK_SIZE] {
+        let mut rng = rand::thread_rng();
+        let mut hand: Vec<usize> = self
+            .holds
+            .iter()
+            .map(|key| card_atlas.position(key).expect("Decks are checked on load"))
+            .collect();
+        let mut candidates = self.candidates(card_atlas);
+        while hand.len() < DECK_SIZE {
+            if self.unique {
+                candidates.retain(|(id, _)| !hand.contains(id));
+            }
+            let dist = WeightedIndex::new(candidates.iter().map(|(_, weight)| *weight))
+                .expect("Decks are checked on load");
+            hand.push(candidates[dist.sample(&mut rng)].0);
+        }
+        hand.shuffle(&mut rng);
+        std::array::from_fn(|i| hand[i])
+    }
+}
+
+/// An opponent to pick for free play instead of the random levels.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpponentProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub deck: DeckSpec,
+}
+
+/// The opponents listed in `opponents.json`.
+#[derive(Deserialize, Debug, Default)]
+pub struct Roster {
+    pub profiles: Vec<OpponentProfile>,
+}
+
+impl Roster {
+    pub fn load(assets: &AssetSource, card_atlas: &CardAtlas) -> Result<Self, AssetError> {
+        let roster: Self = assets.json(OPPONENTS_FILE)?;
+        for profile in &roster.profiles {
+            profile
+                .deck
+                .check(card_atlas)
+                .map_err(|message| AssetError::Invalid {
+                    file: OPPONENTS_FILE.into(),
+                    message: format!("{}: {message}", profile.id),
+                })?;
+        }
+        Ok(roster)
+    }
+}
+
+/// Maps every held card to the names of the opponents holding it, so the
+/// player knows whom to beat for it.
+pub fn holders<'a>(
+    decks: impl IntoIterator<Item = (&'a str, &'a DeckSpec)>,
+) -> HashMap<String, Vec<String>> {
+    let mut holders: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, deck) in decks {
+        for key in &deck.holds {
+            let names = holders.entry(key.clone()).or_default();
+            if !names.iter().any(|held_by| held_by == name) {
+                names.push(name.into());
+            }
+        }
+    }
+    holders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Card, BASE_PACK};
+
+    /// An atlas with one card per entry of `levels`, keyed `base:0`, `base:1`…
+    fn atlas(levels: &[u8]) -> CardAtlas {
+        let cards = levels
+            .iter()
+            .enumerate()
+            .map(|(id, level)| Card {
+                id,
+                name: format!("Card {id}"),
+                level: *level,
+                pow_left: 1,
+                pow_right: 1,
+                pow_top: 1,
+                pow_bottom: 1,
+                element: None,
+                sprite_id: id,
+                pack: BASE_PACK.into(),
+            })
+            .collect();
+        CardAtlas { cards }
+    }
+
+    fn holds(ids: &[usize]) -> Vec<String> {
+        ids.iter().map(|id| format!("base:{id}")).collect()
+    }
+
+    fn level(level: u8, weight: u32) -> Draw {
+        Draw::Level { level, weight }
+    }
+
+    fn card(id: usize, weight: u32) -> Draw {
+        Draw::Card {
+            card: format!("base:{id}"),
+            weight,
+        }
+    }
+
+    #[test]
+    fn unique_deck_deals_holds_and_different_cards() {
+        let card_atlas = atlas(&[1, 1, 2, 2, 2, 2]);
+        let deck = DeckSpec {
+            holds: holds(&[0, 1]),
+            pool: vec![level(2, 1)],
+            unique: true,
+        };
+        deck.check(&card_atlas).unwrap();
+        for _ in 0..20 {
+            let mut hand = deck.deal(&card_atlas);
+            hand.sort_unstable();
+            assert!(hand.windows(2).all(|pair| pair[0] != pair[1]), "{hand:?}");
+            assert_eq!(hand[..2], [0, 1]);
+        }
+    }
+
+    #[test]
+    fn unique_deck_needs_enough_cards() {
+        let card_atlas = atlas(&[1, 1, 2, 2, 2]);
+        let deck = DeckSpec {
+            holds: holds(&[0, 2]),
+            pool: vec![level(2, 1)],
+            unique: true,
+        };
+        assert_eq!(
+            deck.check(&card_atlas).unwrap_err(),
+            "the deck can't deal 5 different cards"
+        );
+    }
+
+    #[test]
+    fn unique_deck_holds_each_card_once() {
+        let card_atlas = atlas(&[1; 8]);
+        let deck = DeckSpec {
+            holds: holds(&[0, 0]),
+            pool: vec![level(1, 1)],
+            unique: true,
+        };
+        assert_eq!(
+            deck.check(&card_atlas).unwrap_err(),
+            "a unique deck holds a card twice"
+        );
+    }
+
+    #[test]
+    fn level_pool_draws_from_that_level() {
+        let card_atlas = atlas(&[1, 1, 3, 3]);
+        let deck = DeckSpec {
+            pool: vec![level(3, 1)],
+            ..DeckSpec::default()
+        };
+        deck.check(&card_atlas).unwrap();
+        assert!(deck.deal(&card_atlas).iter().all(|id| *id >= 2));
+
+        let deck = DeckSpec {
+            pool: vec![level(2, 1)],
+            ..DeckSpec::default()
+        };
+        assert_eq!(deck.check(&card_atlas).unwrap_err(), "level 2 has no cards");
+    }
+
+    #[test]
+    fn zero_weights_are_never_drawn() {
+        let card_atlas = atlas(&[1, 1, 1]);
+        let deck = DeckSpec {
+            pool: vec![card(0, 0), card(1, 1), card(2, 0)],
+            ..DeckSpec::default()
+        };
+        deck.check(&card_atlas).unwrap();
+        assert_eq!(deck.deal(&card_atlas), [1; DECK_SIZE]);
+
+        let deck = DeckSpec {
+            pool: vec![level(1, 0)],
+            ..DeckSpec::default()
+        };
+        assert_eq!(
+            deck.check(&card_atlas).unwrap_err(),
+            "the deck can't deal 5 cards"
+        );
+    }
+
+    #[test]
+    fn full_hand_of_holds_needs_no_pool() {
+        let card_atlas = atlas(&[1; 5]);
+        let deck = DeckSpec {
+            holds: holds(&[4, 3, 2, 1, 0]),
+            ..DeckSpec::default()
+        };
+        deck.check(&card_atlas).unwrap();
+        let mut hand = deck.deal(&card_atlas);
+        hand.sort_unstable();
+        assert_eq!(hand, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn more_than_a_hand_of_holds_is_refused() {
+        let card_atlas = atlas(&[1; 6]);
+        let deck = DeckSpec {
+            holds: holds(&[0, 1, 2, 3, 4, 5]),
+            ..DeckSpec::default()
+        };
+        assert_eq!(
+            deck.check(&card_atlas).unwrap_err(),
+            "more than 5 cards are held"
+        );
+    }
+
+    #[test]
+    fn unknown_cards_are_refused() {
+        let card_atlas = atlas(&[1; 5]);
+        let deck = DeckSpec {
+            holds: holds(&[0]),
+            pool: vec![card(7, 1)],
+            unique: false,
+        };
+        assert_eq!(
+            deck.check(&card_atlas).unwrap_err(),
+            "unknown card `base:7`"
+        );
+    }
+}
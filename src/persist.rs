@@ -0,0 +1,102 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use std::path::Path;
+
+use crate::error::{self, AssetError};
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn parse<T: DeserializeOwned>(path: &Path) -> Option<Result<T, AssetError>> {
+    let file = std::fs::File::open(path).ok()?;
+    Some(error::from_json_reader(
+        &file_name(path),
+        std::io::BufReader::new(file),
+    ))
+}
+
+/// Reads the json file at `path`. A missing file gives `None`, so does an
+/// unreadable one after it is reported, it is replaced on the next save.
+pub fn read<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    parse(path)?.map_err(|e| eprintln!("{e}")).ok()
+}
+
+/// Like `read`, but an unreadable file is first moved aside to `<file>.bak`
+/// so the next save does not lose it.
+pub fn read_or_back_up<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    match parse(path)? {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(".bak");
+            match std::fs::rename(path, &backup) {
+                Ok(()) => eprintln!("{e}, kept as {}.bak", file_name(path)),
+                Err(rename) => eprintln!("{e}, could not keep a copy: {rename}"),
+            }
+            None
+        }
+    }
+}
+
+/// Writes `value` as json to `path`, creating the directory it goes in.
+pub fn write<T: Serialize>(path: &Path, value: &T) -> Result<(), AssetError> {
+    let io_error = |source| AssetError::Io {
+        file: file_name(path),
+        source,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let json = serde_json::to_string_pretty(value).expect("Saved files are always serializable");
+    std::fs::write(path, json).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("triple-triad-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn written_value_reads_back() {
+        let path = temp_dir("write").join("nested").join("values.json");
+        write(&path, &vec![1, 2, 3]).unwrap();
+        assert_eq!(read::<Vec<u32>>(&path), Some(vec![1, 2, 3]));
+        std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_or_unreadable_file_reads_as_none() {
+        let dir = temp_dir("read");
+        let path = dir.join("values.json");
+        assert_eq!(read::<Vec<u32>>(&path), None);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "[1, ").unwrap();
+        assert_eq!(read::<Vec<u32>>(&path), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_file_is_backed_up() {
+        let dir = temp_dir("back-up");
+        let path = dir.join("values.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "[1, ").unwrap();
+        assert_eq!(read_or_back_up::<Vec<u32>>(&path), None);
+        assert!(!path.exists());
+        let backup = std::fs::read_to_string(dir.join("values.json.bak")).unwrap();
+        assert_eq!(backup, "[1, ");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::core::{DuelOutcome, Rules, Suit};
+use crate::error::AssetError;
+use crate::persist;
+use crate::utils::Rule;
+
+pub const PROFILES_FILE: &str = "profiles.json";
+pub const PROFILE_NAME_MAX_LEN: usize = 16;
+/// Older matches are dropped from the history.
+pub const HISTORY_LEN: usize = 50;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Record {
+    fn add(&mut self, outcome: DuelOutcome) {
+        match outcome {
+            DuelOutcome::Win => self.wins += 1,
+            DuelOutcome::Lose => self.losses += 1,
+            DuelOutcome::Draw => self.draws += 1,
+        }
+    }
+
+    pub const fn matches(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+}
+
+/// How one card fared across the player's matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct CardStats {
+    /// Matches the player had the card in hand.
+    pub played: u32,
+    /// Matches the opponent had it.
+    pub faced: u32,
+    /// Times the player captured it from the opponent.
+    pub captured: u32,
+}
+
+impl CardStats {
+    /// Share of the opponent's copies the player captured.
+    pub fn capture_rate(&self) -> Option<f32> {
+        (self.faced > 0).then(|| ratio(self.captured) / ratio(self.faced))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatchRecord {
+    pub outcome: DuelOutcome,
+    pub opponent: String,
+    pub difficulty: usize,
+    pub rules: String,
+    /// The player's score first.
+    pub score: [u8; 2],
+    pub sudden_death_rounds: u32,
+    pub cards: Vec<String>,
+    /// Every card put down, in order and across Sudden Death rounds.
+    #[serde(default)]
+    pub moves: Vec<Move>,
+}
+
+/// A card put down during a match.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub suit: Suit,
+    /// Key of the card, e.g. `base:12`.
+    pub card: String,
+    pub cell: usize,
+}
+
+/// Everything a finished match adds to a profile.
+#[derive(Debug, Clone)]
+pub struct MatchSummary {
+    pub record: MatchRecord,
+    pub same: u32,
+    pub plus: u32,
+    pub combo: u32,
+    /// Most cards the player captured with one move.
+    pub best_chain: u32,
+    /// Levels of the player's cards.
+    pub levels: Vec<u8>,
+    /// Keys of the cards the opponent played.
+    pub faced: Vec<String>,
+    /// Keys of the opponent's cards the player ended up controlling.
+    pub captured: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub record: Record,
+    pub by_difficulty: BTreeMap<usize, Record>,
+    /// Keyed by the enabled rules, see `rules_label`.
+    pub by_rules: BTreeMap<String, Record>,
+    /// Combos triggered by the player.
+    pub same: u32,
+    pub plus: u32,
+    pub combo: u32,
+    pub sudden_death_rounds: u32,
+    pub best_chain: u32,
+    pub cards: HashMap<String, CardStats>,
+    /// The latest match first.
+    pub history: Vec<MatchRecord>,
+    /// Matches counted towards each locked achievement, by id.
+    pub achievements: BTreeMap<String, u32>,
+    /// Ids of the unlocked achievements, in the order they were unlocked.
+    pub unlocked: Vec<String>,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    fn record_match(&mut self, summary: MatchSummary) {
+        let MatchSummary {
+            record,
+            same,
+            plus,
+            combo,
+            best_chain,
+            levels: _,
+            faced,
+            captured,
+        } = summary;
+
+        self.record.add(record.outcome);
+        self.by_difficulty
+            .entry(record.difficulty)
+            .or_default()
+            .add(record.outcome);
+        self.by_rules
+            .entry(record.rules.clone())
+            .or_default()
+            .add(record.outcome);
+        self.same += same;
+        self.plus += plus;
+        self.combo += combo;
+        self.sudden_death_rounds += record.sudden_death_rounds;
+        self.best_chain = self.best_chain.max(best_chain);
+        for key in &record.cards {
+            self.cards.entry(key.clone()).or_default().played += 1;
+        }
+        for key in faced {
+            self.cards.entry(key).or_default().faced += 1;
+        }
+        for key in captured {
+            self.cards.entry(key).or_default().captured += 1;
+        }
+        self.history.insert(0, record);
+        self.history.truncate(HISTORY_LEN);
+    }
+
+    /// The cards the player used most, most played first.
+    pub fn most_played(&self) -> Vec<(&str, CardStats)> {
+        let mut cards: Vec<(&str, CardStats)> = self
+            .cards
+            .iter()
+            .filter(|(_, stats)| stats.played > 0)
+            .map(|(key, stats)| (key.as_str(), *stats))
+            .collect();
+        cards.sort_by(|a, b| b.1.played.cmp(&a.1.played).then(a.0.cmp(b.0)));
+        cards
+    }
+
+    /// The opponent's cards the player met, the best capture rate first.
+    pub fn best_captured(&self) -> Vec<(&str, CardStats)> {
+        let mut cards: Vec<(&str, CardStats)> = self
+            .cards
+            .iter()
+            .filter(|(_, stats)| stats.faced > 0)
+            .map(|(key, stats)| (key.as_str(), *stats))
+            .collect();
+        cards.sort_by(|a, b| {
+            let rate = |stats: &CardStats| stats.capture_rate().unwrap_or_default();
+            rate(&b.1)
+                .total_cmp(&rate(&a.1))
+                .then(b.1.faced.cmp(&a.1.faced))
+                .then(a.0.cmp(b.0))
+        });
+        cards
+    }
+}
+
+/// The player profiles kept in the user data directory, results go to the
+/// active one.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Profiles {
+    #[serde(skip)]
+    path: PathBuf,
+    pub active: usize,
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// Reads the profiles from `dir`, starting with one default profile
+    /// without them. An unreadable file is kept as `profiles.json.bak`.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(PROFILES_FILE);
+        let mut profiles: Self = persist::read_or_back_up(&path).unwrap_or_default();
+        profiles.path = path;
+        if profiles.profiles.is_empty() {
+            profiles.profiles.push(Profile::new("Player"));
+        }
+        profiles.active = profiles.active.min(profiles.profiles.len() - 1);
+        profiles
+    }
+
+    pub fn save(&self) -> Result<(), AssetError> {
+        persist::write(&self.path, self)
+    }
+
+    pub fn active(&self) -> &Profile {
+        &self.profiles[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Profile {
+        &mut self.profiles[self.active]
+    }
+
+    /// Adds a profile and makes it the active one.
+    pub fn add(&mut self, name: &str) {
+        self.profiles.push(Profile::new(name));
+        self.select(self.profiles.len() - 1);
+    }
+
+    pub fn select(&mut self, n: usize) {
+        self.active = n;
+        if let Err(e) = self.save() {
+            eprintln!("{e}");
+        }
+    }
+
+    pub fn record_match(&mut self, summary: MatchSummary) {
+        self.profiles[self.active].record_match(summary);
+        if let Err(e) = self.save() {
+            eprintln!("{e}");
+        }
+    }
+}
+
+/// Names the enabled rules, e.g. `Open, Same`, or `None`.
+pub fn rules_label(rules: &Rules) -> String {
+    let names: Vec<&str> = Rule::iterator()
+        .filter(|rule| rules.enabled(**rule))
+        .map(|rule| rule.name())
+        .collect();
+    if names.is_empty() {
+        "None".into()
+    } else {
+        names.join(", ")
+    }
+}
+
+fn ratio(n: u32) -> f32 {
+    f32::from(u16::try_from(n).unwrap_or(u16::MAX))
+}
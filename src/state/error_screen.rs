@@ -0,0 +1,57 @@
+use ggez::event::{EventHandler, MouseButton};
+use ggez::graphics::{Canvas, Color, PxScale, Text, TextFragment};
+use ggez::input::keyboard::KeyCode;
+use ggez::{Context, GameError, GameResult};
+
+use crate::consts::WINDOW_DIMENSIONS;
+use crate::layout::Layout;
+
+const MARGIN: f32 = 40.0;
+const TITLE_SIZE: f32 = 32.0;
+const MESSAGE_SIZE: f32 = 18.0;
+
+/// Shown instead of the game when the assets could not be loaded.
+pub struct ErrorScreen {
+    message: String,
+}
+
+impl ErrorScreen {
+    pub fn new(error: &GameError) -> Self {
+        Self {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl EventHandler<GameError> for ErrorScreen {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape)
+            || ctx.mouse.button_just_pressed(MouseButton::Left)
+        {
+            ctx.request_quit();
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let mut canvas = Canvas::from_frame(ctx, Color::from([0.1, 0.2, 0.3, 1.0]));
+        canvas.set_screen_coordinates(Layout::from_ctx(ctx).screen);
+
+        let mut text = Text::new(TextFragment {
+            text: "Unable to load the game assets\n\n".into(),
+            color: Some(Color::WHITE),
+            font: None,
+            scale: Some(PxScale::from(TITLE_SIZE)),
+        });
+        text.add(TextFragment {
+            text: format!("{}\n\nClick or press Esc to quit.", self.message),
+            color: Some(Color::from_rgb(200, 200, 200)),
+            font: None,
+            scale: Some(PxScale::from(MESSAGE_SIZE)),
+        });
+        text.set_bounds([WINDOW_DIMENSIONS[0] - MARGIN * 2.0, f32::INFINITY]);
+
+        canvas.draw(&text, [MARGIN, MARGIN]);
+        canvas.finish(ctx)
+    }
+}
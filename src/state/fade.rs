@@ -5,7 +5,7 @@ use ggez::{
 
 use tween::{Linear, Tweener};
 
-use crate::{consts::WINDOW_DIMENSIONS, utils::Event};
+use crate::{consts::WINDOW_DIMENSIONS, events::EventBus, utils::Event};
 
 pub const DURATION: f32 = 2.0;
 
@@ -42,6 +42,11 @@ impl Fade {
             fade_out,
         }
     }
+    pub fn set_duration(&mut self, duration: f32) {
+        self.fade_in.tweener = Tweener::new(1.0, 0.0, duration, Linear);
+        self.fade_out.tweener = Tweener::new(0.0, 1.0, duration, Linear);
+    }
+
     pub fn fade_in(&mut self) {
         self.fade_in.active = true;
         self.fade_in.tweener.current_time = 0.0;
@@ -51,27 +56,19 @@ impl Fade {
         self.fade_out.tweener.current_time = 0.0;
     }
 
-    pub fn fade_in_update(&mut self, dt: f32) -> Option<Event> {
-        if !self.fade_in.active {
-            self.fade_in();
-        }
+    pub fn fade_in_update(&mut self, dt: f32, events: &mut EventBus<Event>) {
         self.color.a = self.fade_in.tweener.move_by(dt);
         self.fade_in.active = !self.fade_in.tweener.is_finished();
         if !self.fade_in.active {
-            return Some(Event::Finished);
+            events.publish(Event::Finished);
         }
-        None
     }
-    pub fn fade_out_update(&mut self, dt: f32) -> Option<Event> {
-        if !self.fade_out.active {
-            self.fade_out();
-        }
+    pub fn fade_out_update(&mut self, dt: f32, events: &mut EventBus<Event>) {
         self.color.a = self.fade_out.tweener.move_by(dt);
         self.fade_out.active = !self.fade_out.tweener.is_finished();
         if !self.fade_out.active {
-            return Some(Event::Finished);
+            events.publish(Event::Finished);
         }
-        None
     }
 
     pub fn draw(&self, canvas: &mut Canvas) {
@@ -1,5 +1,8 @@
+mod error_screen;
 mod fade;
 mod play_state;
+pub mod scene;
 
+pub use error_screen::ErrorScreen;
 pub use play_state::PlayState;
 pub use fade::Fade;
@@ -10,7 +10,7 @@ use crate::utils;
 
 use super::opponent::AiEvent;
 
-const TIMEOUT: f32 = 1.0;
+pub const TIMEOUT: f32 = 1.0;
 
 #[derive(Clone, Debug)]
 struct Cell {
@@ -432,6 +432,7 @@ pub struct Opponent {
     pub actions: Vec<Action>,
     maybe_move: Option<Move>,
     timer: f32,
+    pub timeout: f32,
     solve_result: Arc<Mutex<Move>>,
     thread_status: Arc<Mutex<ThreadStatus>>,
 }
@@ -446,6 +447,7 @@ impl Opponent {
             actions: vec![],
             maybe_move: None,
             timer: 0.0,
+            timeout: TIMEOUT,
             solve_result,
             thread_status,
         }
@@ -505,7 +507,7 @@ impl Opponent {
                     *self.solve_result.lock().unwrap() = Move::new();
                     red_hand.set_focus(i);
                     self.solve(i, &board, self.diffuculty);
-                    self.timer = TIMEOUT;
+                    self.timer = self.timeout;
                     return Some(AiEvent::Focus);
                 }
                 ThreadStatus::Active => return None,
@@ -535,7 +537,7 @@ impl Opponent {
                 let from = self.maybe_move.as_ref().unwrap().from;
                 let to = self.maybe_move.as_ref().unwrap().to;
                 red_hand.set_focus(from);
-                self.timer = TIMEOUT;
+                self.timer = self.timeout;
                 self.actions.pop();
                 self.actions.push(Action::Put(from, to));
                 return Some(AiEvent::Focus);
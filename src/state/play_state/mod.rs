@@ -1,35 +1,83 @@
 use ggez::graphics::{Canvas, Image, InstanceArray};
-// use ggez::graphics::{DrawParam, Text};
 
+use ggez::input::keyboard::KeyCode;
 use ggez::Context;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+use crate::achievements::Achievements;
+use crate::audio::Track;
+use crate::campaign::{Campaign, Progress};
+use crate::collection::Collection;
+use crate::config::{Config, FirstTurn};
 use crate::core::DuelOutcome;
-use crate::core::{CardAtlas, Suit};
+use crate::core::{CardAtlas, Rules, Suit};
+use crate::deck::Decks;
+use crate::events::{EventBus, GameEvent, Subscriber};
 use crate::graphics::sprite::Atlas;
+use crate::opponents::{self, Roster};
+use crate::profile::{self, MatchRecord, MatchSummary, Profiles};
+use crate::state::scene::{Hook, SceneStack};
 use crate::utils::{Event, Sfx};
 
 mod ai;
 mod opponent;
 pub mod state;
+mod suspend;
 
 pub use state::Hand;
 
+use state::Album;
 use state::Banner;
 use state::Board;
+use state::CampaignMap;
 use state::CardPicker;
 use state::CoinFlip;
+use state::DeckBuilder;
 use state::Menu;
-#[allow(dead_code)]
-#[derive(Debug)]
+use state::Pick;
+use state::Prompt;
+use state::Settings;
+use state::Stats;
+use state::Toast;
+use suspend::{SuspendedDuel, SUSPEND_FILE};
+
+const PAUSE_CHOICES: [(&str, Event); 5] = [
+    ("Resume", Event::Finished),
+    ("Restart", Event::Restart),
+    ("Concede", Event::Concede),
+    ("Settings", Event::EditSettings),
+    ("Main menu", Event::ReturnToMenu),
+];
+const RESUME_CHOICES: [(&str, Event); 2] = [
+    ("Continue", Event::ContinueDuel),
+    ("Discard", Event::Finished),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum State {
     Menu,
+    DeckBuilder,
+    Album,
+    Campaign,
+    Stats,
+    Settings,
     CardPick,
     CoinFlip,
     Play,
+    Pause,
+    Resume,
     Fin,
 }
 
+/// The rules and hands a duel started with, to restart it.
+struct DuelSetup {
+    rules: Rules,
+    red: Vec<usize>,
+    blue: Vec<usize>,
+}
+
 // pub struct GameSummary {
 //     outcome: DuelOutcome,
 //     sudden_death: bool,
@@ -38,31 +86,115 @@ enum State {
 pub struct PlayState {
     menu: Menu,
     card_pick: CardPicker,
-    _coin_flip: CoinFlip,
+    deck_builder: DeckBuilder,
+    decks: Decks,
+    album: Album,
+    collection: Collection,
+    campaign: Campaign,
+    progress: Progress,
+    campaign_map: CampaignMap,
+    /// The campaign opponent of the current match.
+    campaign_match: Option<usize>,
+    roster: Roster,
+    /// The free play opponent, `None` draws from the enabled levels.
+    profile: Option<usize>,
+    /// Card keys to the names of the opponents holding them.
+    holders: HashMap<String, Vec<String>>,
+    profiles: Profiles,
+    stats: Stats,
+    achievements: Achievements,
+    toast: Toast,
+    settings: Settings,
+    config: Config,
+    coin_flip: CoinFlip,
     play: Board,
+    duel_setup: Option<DuelSetup>,
+    /// The duel left on the last run, until it is continued or discarded.
+    suspended: Option<SuspendedDuel>,
+    /// Where the duel in progress is saved at the start of every turn.
+    suspend_dir: PathBuf,
+    pause: Prompt,
+    resume: Prompt,
     fin: Banner,
-    state_stack: Vec<State>,
+    /// What the screen on top asked for during the frame.
+    events: EventBus<Event>,
+    state_stack: SceneStack<State>,
 }
 
 impl PlayState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ctx: &mut Context,
         card_atlas: &Rc<CardAtlas>,
         sprite_sheet: &Rc<Atlas>,
         bg_image: &Rc<Image>,
+        campaign: Campaign,
+        roster: Roster,
+        achievements: Achievements,
     ) -> Self {
-        let menu = Menu::new(ctx);
+        let menu = Menu::new(ctx, &card_atlas.levels());
         let card_pick = CardPicker::new(ctx, card_atlas, sprite_sheet);
-        let _coin_flip = CoinFlip {};
-        let play = Board::empty(ctx, card_atlas, sprite_sheet, bg_image);
+        let deck_builder = DeckBuilder::new(ctx, card_atlas, sprite_sheet);
+        let decks = Decks::load(ctx.fs.user_data_dir());
+        let album = Album::new(ctx, card_atlas, sprite_sheet);
+        let collection = Collection::load(ctx.fs.user_data_dir());
+        let progress = Progress::load(ctx.fs.user_data_dir());
+        let campaign_map = CampaignMap::new(ctx, &campaign, card_atlas);
+        let holders = opponents::holders(
+            campaign
+                .opponents
+                .iter()
+                .map(|opponent| (opponent.name.as_str(), &opponent.deck))
+                .chain(
+                    roster
+                        .profiles
+                        .iter()
+                        .map(|profile| (profile.name.as_str(), &profile.deck)),
+                ),
+        );
+        let profiles = Profiles::load(ctx.fs.user_data_dir());
+        let stats = Stats::new(ctx, card_atlas);
+        let toast = Toast::new(ctx);
+        let settings = Settings::new(ctx);
+        let config = Config::load(ctx.fs.user_config_dir());
+        let coin_flip = CoinFlip::new(sprite_sheet);
+        let mut play = Board::empty(ctx, card_atlas, sprite_sheet, bg_image);
+        play.apply_settings(&config.gameplay);
+        let suspend_dir = ctx.fs.user_data_dir().to_path_buf();
+        let suspended = SuspendedDuel::load(&suspend_dir);
+        let pause = Prompt::new(ctx, "Paused", &PAUSE_CHOICES);
+        let resume = Prompt::new(ctx, "Duel left", &RESUME_CHOICES);
         let fin = Banner::new(sprite_sheet);
-        let state_stack = vec![State::Play];
+        let state_stack = SceneStack::new(&[State::Play]);
         Self {
             menu,
             card_pick,
-            _coin_flip,
+            deck_builder,
+            decks,
+            album,
+            collection,
+            campaign,
+            progress,
+            campaign_map,
+            campaign_match: None,
+            roster,
+            profile: None,
+            holders,
+            profiles,
+            stats,
+            achievements,
+            toast,
+            settings,
+            config,
+            coin_flip,
             play,
+            duel_setup: None,
+            suspended,
+            suspend_dir,
+            pause,
+            resume,
             fin,
+            events: EventBus::default(),
             state_stack,
         }
     }
@@ -70,144 +202,671 @@ impl PlayState {
         self.play.init();
         self.menu.init(&self.play.rules);
         self.card_pick.init();
-        self.state_stack.clear();
+        self.card_pick.set_decks(&self.decks);
         self.fin.init();
-        self.state_stack.push(State::Play);
-        self.state_stack.push(State::CoinFlip);
-        self.state_stack.push(State::Menu);
+        // A campaign match goes back to the ladder.
+        let screen = if self.campaign_match.take().is_some() {
+            State::Campaign
+        } else {
+            State::Menu
+        };
+        self.state_stack
+            .set(&[State::Play, State::CoinFlip, screen]);
+        if self.suspended.is_some() {
+            self.state_stack.push(State::Resume);
+        }
+        self.run_hooks();
     }
     pub fn clear(&mut self) {
-        self.state_stack.clear();
         self.play.clear();
-        self.state_stack.push(State::Play);
-        self.state_stack.push(State::CoinFlip);
+        self.state_stack.set(&[State::Play, State::CoinFlip]);
+        self.run_hooks();
+    }
+    pub fn update(&mut self, ctx: &mut Context, events: &mut EventBus<Event>) {
+        self.update_state(ctx, events);
+        self.run_hooks();
     }
-    pub fn update(&mut self, ctx: &mut Context) -> Option<Event> {
-        if self.state_stack.is_empty() {
-            return Some(Event::Quit);
-        }
 
-        if let Some(state) = self.state_stack.last() {
-            match state {
-                State::Menu => return self.update_menu(ctx),
-                State::CardPick => {
-                    if let Some((id, count)) = self.card_pick.update(ctx) {
-                        self.play.blue_hand.add_card(id, count, true);
-                        if count >= 4 {
-                            self.state_stack.pop();
-                            self.play.next_state();
-                        }
-                        return Some(Event::PlaySound(Sfx::Select));
-                    }
-                    self.play.update(ctx);
+    /// Sets screens up as they are entered and saves what they changed as
+    /// they are left.
+    fn run_hooks(&mut self) {
+        for hook in self.state_stack.take_hooks() {
+            match hook {
+                Hook::Enter(State::DeckBuilder) => self.deck_builder.init(&self.decks),
+                Hook::Exit(State::DeckBuilder) => self.card_pick.set_decks(&self.decks),
+                Hook::Enter(State::Album) => self.album.init(),
+                Hook::Enter(State::Campaign) => {
+                    self.campaign_map.init(&self.campaign, &self.progress);
                 }
-                State::CoinFlip => {
-                    self.play.first_turn(CoinFlip::first());
+                Hook::Enter(State::Stats) => self.stats.init(),
+                // Under the menu or the card picker it waits for its turn.
+                Hook::Enter(State::CoinFlip) | Hook::Resume(State::CoinFlip)
+                    if self.state_stack.last() == Some(&State::CoinFlip) =>
+                {
+                    self.start_coin_flip();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Runs the screen on top, then acts on what it asked for. Sounds and
+    /// what is up to the app go on to `events`.
+    fn update_state(&mut self, ctx: &mut Context, events: &mut EventBus<Event>) {
+        let Some(&state) = self.state_stack.last() else {
+            events.publish(Event::Quit);
+            return;
+        };
+        self.toast.update(ctx.time.delta().as_secs_f32());
+
+        match state {
+            State::Menu => self.menu.update(
+                self.play.opponent.difficulty(),
+                &self.play.rules,
+                self.play.opponent.cards(),
+                self.profile
+                    .map_or("Random", |n| self.roster.profiles[n].name.as_str()),
+                ctx,
+                &mut self.events,
+            ),
+            State::DeckBuilder => self
+                .deck_builder
+                .update(ctx, &mut self.decks, &mut self.events),
+            State::Album => self.album.update(ctx, &mut self.events),
+            State::Campaign => {
+                self.campaign_map
+                    .update(ctx, &self.campaign, &self.progress, &mut self.events);
+            }
+            State::Stats => self.stats.update(ctx, &mut self.profiles, &mut self.events),
+            State::Settings => self
+                .settings
+                .update(ctx, &mut self.config, &mut self.events),
+            State::CardPick => self.update_card_pick(ctx),
+            State::CoinFlip => {
+                let dt = ctx.time.delta().as_secs_f32();
+                self.play.animate(dt);
+                if let Some(first) = self.coin_flip.update(dt) {
+                    self.play.first_turn(first);
                     self.state_stack.pop();
                 }
-                State::Play => {
-                    if let Some(e) = self.play.update(ctx) {
-                        match e {
-                            Event::GameSummary(DuelOutcome::Draw, true) => {
-                                self.play.deal_sudden_death();
-                                self.state_stack.push(State::CoinFlip);
-                                return None;
-                            }
-                            Event::GameSummary(outcome, _) => {
-                                self.fin.outcome = outcome;
-                                self.state_stack.push(State::Fin);
-                            }
-                            Event::PlaySound(s) => {
-                                return Some(Event::PlaySound(s));
-                            }
-                            _ => unreachable!(),
-                        }
-                    }
+            }
+            State::Play => {
+                if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+                    self.state_stack.push(State::Pause);
+                    events.publish(Event::PlaySound(Sfx::Select));
+                    return;
                 }
-                State::Fin => {
-                    if let Some(e) = self.fin.update(ctx) {
-                        match e {
-                            Event::Finished => return Some(Event::Finished),
-                            Event::PlaySound(s) => return Some(Event::PlaySound(s)),
-                            _ => unreachable!(),
-                        }
-                    }
+                self.play.update(ctx, &mut self.events);
+            }
+            State::Pause => self.pause.update(ctx, &mut self.events),
+            State::Resume => self.resume.update(ctx, &mut self.events),
+            State::Fin => self.fin.update(ctx, &mut self.events),
+        }
+
+        for event in self.events.drain() {
+            self.handle(state, event, events);
+        }
+    }
+
+    fn handle(&mut self, state: State, event: Event, events: &mut EventBus<Event>) {
+        match (state, event) {
+            (_, Event::PlaySound(sfx)) => events.publish(Event::PlaySound(sfx)),
+            (State::Menu, event) => self.handle_menu(event, events),
+            (
+                State::DeckBuilder | State::Album | State::Campaign | State::Stats,
+                Event::Finished,
+            ) => {
+                self.state_stack.replace(State::Menu);
+                events.publish(Event::PlaySound(Sfx::Cancel));
+            }
+            (State::Campaign, Event::PlayCampaign(n)) => {
+                let opponent = &self.campaign.opponents[n];
+                self.play.rules = opponent.rules.clone();
+                self.play.opponent.set_difficulty(opponent.ai.difficulty);
+                if let Some(think_delay) = opponent.ai.think_delay {
+                    self.play.opponent.set_think_delay(think_delay);
                 }
+                self.campaign_match = Some(n);
+                let red_hand = self.campaign.new_hand(n, &self.play.card_atlas);
+                self.start_duel(&red_hand);
+                events.publish(Event::PlaySound(Sfx::Select));
             }
+            (State::Settings, Event::Finished) => {
+                self.state_stack.pop();
+                if self.state_stack.last() != Some(&State::Pause) {
+                    self.state_stack.push(State::Menu);
+                }
+                events.publish(Event::PlaySound(Sfx::Cancel));
+            }
+            (State::Settings, Event::SettingsChanged) => {
+                // A paused duel keeps its rules and its AI.
+                let rules = self.play.rules.clone();
+                let opponent = &self.play.opponent;
+                let (difficulty, think_delay) = (opponent.difficulty(), opponent.think_delay());
+                self.play.apply_settings(&self.config.gameplay);
+                if self.state_stack.iter().any(|state| *state == State::Pause) {
+                    self.play.rules = rules;
+                    self.play.opponent.set_difficulty(difficulty);
+                    self.play.opponent.set_think_delay(think_delay);
+                }
+                events.publish(Event::SettingsChanged);
+            }
+            (State::Play, Event::GameSummary(DuelOutcome::Draw, true)) => {
+                self.play.deal_sudden_death();
+                self.state_stack.push(State::CoinFlip);
+            }
+            (State::Play, Event::GameSummary(outcome, _)) => self.finish(outcome),
+            (State::Pause, event) => self.handle_pause(event, events),
+            (State::Resume, Event::ContinueDuel) => self.continue_duel(events),
+            (State::Resume, Event::Finished) => {
+                self.suspended = None;
+                SuspendedDuel::remove(&self.suspend_dir);
+                self.state_stack.pop();
+                events.publish(Event::PlaySound(Sfx::Cancel));
+            }
+            (State::Fin, Event::Finished) => events.publish(Event::Finished),
+            (state, event) => unreachable!("{event:?} on {state:?}"),
+        }
+    }
+
+    fn update_card_pick(&mut self, ctx: &mut Context) {
+        let hand = &mut self.play.blue_hand;
+        let sfx = match self.card_pick.update(ctx, hand) {
+            Some(Pick::Card(id)) => {
+                let n = u8::try_from(hand.card_count()).expect("Value is too big");
+                hand.add_card(id, n, true);
+                Sfx::Select
+            }
+            Some(Pick::Deck(ids)) => {
+                hand.clear_cards();
+                for (n, id) in ids.into_iter().enumerate() {
+                    let n = u8::try_from(n).expect("Value is too big");
+                    hand.add_card(id, n, true);
+                }
+                Sfx::Select
+            }
+            Some(Pick::Remove(n)) => {
+                hand.remove_card(n);
+                Sfx::Cancel
+            }
+            Some(Pick::Mark(n)) => {
+                match n {
+                    Some(n) => hand.set_focus(n),
+                    None => hand.reset_foucus(),
+                }
+                Sfx::Move
+            }
+            Some(Pick::Swap(a, b)) => {
+                hand.swap_cards(a, b);
+                Sfx::Select
+            }
+            Some(Pick::Start) => {
+                hand.reset_foucus();
+                if let Some(setup) = &mut self.duel_setup {
+                    setup.blue = hand.card_ids();
+                }
+                self.state_stack.pop();
+                self.play.next_state();
+                Sfx::Select
+            }
+            Some(Pick::Hover) => {
+                self.play.update(ctx, &mut self.events);
+                Sfx::Hover
+            }
+            Some(Pick::Filter) => {
+                self.play.update(ctx, &mut self.events);
+                Sfx::Select
+            }
+            Some(Pick::Full) => {
+                self.play.update(ctx, &mut self.events);
+                Sfx::Cancel
+            }
+            None => {
+                self.play.update(ctx, &mut self.events);
+                return;
+            }
+        };
+        self.events.publish(Event::PlaySound(sfx));
+    }
+
+    pub fn text_input(&mut self, character: char) {
+        match self.state_stack.last() {
+            Some(State::DeckBuilder) => self.deck_builder.text_input(character),
+            Some(State::CardPick) => self.card_pick.text_input(character),
+            Some(State::Stats) => self.stats.text_input(character),
+            _ => {}
+        }
+    }
+
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The music that fits the current screen.
+    pub fn track(&self) -> Track {
+        match self.state_stack.last() {
+            Some(
+                State::Menu
+                | State::DeckBuilder
+                | State::Album
+                | State::Campaign
+                | State::Stats
+                | State::Settings,
+            )
+            | None
+                if !self.state_stack.iter().any(|state| *state == State::Pause) =>
+            {
+                Track::Menu
+            }
+            Some(State::Fin) => match self.fin.outcome {
+                DuelOutcome::Win => Track::Victory,
+                DuelOutcome::Lose => Track::Defeat,
+                DuelOutcome::Draw => Track::Duel,
+            },
+            _ => Track::Duel,
+        }
+    }
+
+    /// Hands the board events of the frame to the subscribers of the play
+    /// state and returns them for the ones outside of it.
+    pub fn publish(&mut self) -> Vec<GameEvent> {
+        let events = self.play.events.drain();
+        for event in &events {
+            self.play.stats.notify(event);
+            self.achievements.notify(event);
+        }
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::TurnStarted(_)))
+        {
+            self.suspend_duel();
+        }
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::MatchRecorded(_)))
+        {
+            self.unlock_achievements();
+        }
+        events
+    }
+
+    /// Counts the recorded match towards the achievements of the active
+    /// profile and announces the ones it unlocks.
+    fn unlock_achievements(&mut self) {
+        for achievement in self.achievements.unlock(self.profiles.active_mut()) {
+            self.toast.push(
+                &format!("Achievement: {}", achievement.name),
+                &achievement.description,
+            );
+        }
+        if let Err(e) = self.profiles.save() {
+            eprintln!("{e}");
         }
-        None
     }
 
     pub fn turn_marker_status(&self) -> [bool; 2] {
+        if self.state_stack.last() == Some(&State::CoinFlip) {
+            let cursor = self.coin_flip.cursor();
+            return [cursor == Some(Suit::Red), cursor == Some(Suit::Blue)];
+        }
         self.play.turn_marker_status()
     }
 
-    fn update_menu(&mut self, ctx: &mut Context) -> Option<Event> {
-        if let Some(e) = self.menu.update(
-            self.play.opponent.difficulty(),
-            &self.play.rules,
-            &self.play.opponent.cards(),
-            ctx,
-        ) {
-            match e {
-                Event::Play => {
-                    self.play.clear();
-                    self.play.red_hand = Hand::from_ids(
-                        Suit::Red,
-                        false,
-                        &self.play.opponent.new_hand(),
-                        &self.play.card_atlas,
-                        &self.play.sprite_sheet,
-                    );
+    /// Sets the cursor off to pick the side that starts the round.
+    fn start_coin_flip(&mut self) {
+        let last_outcome = self
+            .profiles
+            .active()
+            .history
+            .first()
+            .map(|record| record.outcome);
+        match (self.config.gameplay.first_turn, last_outcome) {
+            (FirstTurn::Player, _) => self.coin_flip.point(Suit::Blue),
+            (FirstTurn::Loser, Some(DuelOutcome::Win)) if self.play.round == 0 => {
+                self.coin_flip.point(Suit::Red);
+            }
+            (FirstTurn::Loser, Some(DuelOutcome::Lose)) if self.play.round == 0 => {
+                self.coin_flip.point(Suit::Blue);
+            }
+            _ => self.coin_flip.toss(&mut self.play.rng()),
+        }
+    }
 
-                    self.state_stack.pop();
+    /// Adds the cards of the finished match to the collection.
+    fn record_match(&mut self, outcome: DuelOutcome) {
+        let card_atlas = &self.play.card_atlas;
+        let played: Vec<String> = self
+            .play
+            .match_cards()
+            .map(|card| card_atlas.cards[card.id].key())
+            .collect();
+        let captured = self.captured_keys();
+        self.collection.record_match(
+            played.iter().map(String::as_str),
+            captured.iter().map(String::as_str),
+            matches!(outcome, DuelOutcome::Win),
+        );
+    }
 
-                    // if self.play.rules.open {
-                    //     self.state_stack.push(State::OpenCards);
-                    //     self.state_stack.pop();
-                    // }
-                    if self.play.rules.random {
-                        self.play.blue_hand = Hand::from_ids(
-                            Suit::Blue,
-                            true,
-                            &self.play.opponent.new_hand(),
-                            &self.play.card_atlas,
-                            &self.play.sprite_sheet,
-                        );
-                        return Some(Event::PlaySound(Sfx::Select));
-                    }
-
-                    self.play.wait_for_pick();
-                    self.state_stack.push(State::CardPick);
-                    return Some(Event::PlaySound(Sfx::Select));
-                }
-                Event::Quit => {
-                    self.state_stack.clear();
-                    return Some(Event::PlaySound(Sfx::Cancel));
-                }
-                Event::ChangeRule(r) => {
-                    self.play.toggle_rule(r);
-                    return Some(Event::PlaySound(Sfx::Select));
-                }
-                Event::ChangeDifficulty => {
-                    let curr = self.play.opponent.difficulty();
-                    let d = if curr >= 3 { 1 } else { curr + 1 };
-                    self.play.opponent.set_difficulty(d);
-                    return Some(Event::PlaySound(Sfx::Select));
-                }
-                Event::ToggleCards(n) => {
-                    self.play.opponent.toogle_cards(n);
+    /// The cards a side started the duel with.
+    fn starting_hand(&self, side: Suit) -> &[usize] {
+        self.duel_setup.as_ref().map_or(&[], |setup| match side {
+            Suit::Red => &setup.red,
+            Suit::Blue => &setup.blue,
+        })
+    }
 
-                    return Some(Event::PlaySound(Sfx::Select));
-                }
-                Event::None => {},
-                _ => {
-                    unreachable!();
+    /// Keys of the opponent's cards the player ends the match with. Sudden
+    /// Death makes captured cards their new owner's, so they are told apart
+    /// from the hand the player started with.
+    fn captured_keys(&self) -> Vec<String> {
+        let card_atlas = &self.play.card_atlas;
+        let mut own = self.starting_hand(Suit::Blue).to_vec();
+        self.play
+            .match_cards()
+            .filter(|card| card.controller == Suit::Blue)
+            .filter(|card| match own.iter().position(|id| *id == card.id) {
+                Some(n) => {
+                    own.swap_remove(n);
+                    false
                 }
-            };
+                None => true,
+            })
+            .map(|card| card_atlas.cards[card.id].key())
+            .collect()
+    }
+
+    /// Adds the finished match to the active player profile.
+    fn record_profile(&mut self, outcome: DuelOutcome) {
+        let card_atlas = &self.play.card_atlas;
+        let keys = |side: Suit| -> Vec<String> {
+            self.starting_hand(side)
+                .iter()
+                .map(|id| card_atlas.cards[*id].key())
+                .collect()
+        };
+        let opponent = match (self.campaign_match, self.profile) {
+            (Some(n), _) => self.campaign.opponents[n].name.clone(),
+            (None, Some(n)) => self.roster.profiles[n].name.clone(),
+            (None, None) => "Random".into(),
+        };
+        let (red_score, blue_score) = self.play.calculate_score();
+        let stats = &self.play.stats;
+        let summary = MatchSummary {
+            record: MatchRecord {
+                outcome,
+                opponent,
+                difficulty: self.play.opponent.difficulty(),
+                rules: profile::rules_label(&self.play.rules),
+                score: [blue_score, red_score]
+                    .map(|score| u8::try_from(score).expect("Value is too big")),
+                sudden_death_rounds: stats.sudden_death_rounds,
+                cards: keys(Suit::Blue),
+                moves: stats.moves.clone(),
+            },
+            same: stats.same,
+            plus: stats.plus,
+            combo: stats.combo,
+            best_chain: stats.best_chain,
+            levels: self
+                .starting_hand(Suit::Blue)
+                .iter()
+                .map(|id| card_atlas.cards[*id].level)
+                .collect(),
+            faced: keys(Suit::Red),
+            captured: self.captured_keys(),
+        };
+        self.play
+            .events
+            .publish(GameEvent::MatchRecorded(Box::new(summary.clone())));
+        self.profiles.record_match(summary);
+    }
+
+    /// Grants the reward the first time a campaign opponent is beaten.
+    fn record_campaign(&mut self, outcome: DuelOutcome) {
+        let Some(n) = self.campaign_match else {
+            return;
+        };
+        let opponent = &self.campaign.opponents[n];
+        if matches!(outcome, DuelOutcome::Win) && self.progress.record_win(opponent) {
+            self.collection.add_reward(&opponent.reward);
         }
+    }
+
+    /// Deals `red_hand` to the opponent and lets the player pick a hand,
+    /// unless the Random rule deals one.
+    fn start_duel(&mut self, red_hand: &[usize]) {
+        self.play.clear();
+        self.play.red_hand = Hand::from_ids(
+            Suit::Red,
+            false,
+            red_hand,
+            &self.play.card_atlas,
+            &self.play.sprite_sheet,
+        );
+
+        self.duel_setup = Some(DuelSetup {
+            rules: self.play.rules.clone(),
+            red: red_hand.to_vec(),
+            blue: Vec::new(),
+        });
+        self.state_stack.pop();
 
-        None
+        // if self.play.rules.open {
+        //     self.state_stack.push(State::OpenCards);
+        //     self.state_stack.pop();
+        // }
+        if self.play.rules.random {
+            let blue_hand = self.play.opponent.new_hand();
+            self.play.blue_hand = Hand::from_ids(
+                Suit::Blue,
+                true,
+                &blue_hand,
+                &self.play.card_atlas,
+                &self.play.sprite_sheet,
+            );
+            if let Some(setup) = &mut self.duel_setup {
+                setup.blue = blue_hand.to_vec();
+            }
+            return;
+        }
+
+        self.play.wait_for_pick();
+        self.state_stack.push(State::CardPick);
+    }
+
+    /// Records the result and shows the banner.
+    fn finish(&mut self, outcome: DuelOutcome) {
+        SuspendedDuel::remove(&self.suspend_dir);
+        self.record_match(outcome);
+        self.record_profile(outcome);
+        self.record_campaign(outcome);
+        self.fin.outcome = outcome;
+        self.fin.rounds = self.play.round;
+        self.state_stack.push(State::Fin);
+    }
+
+    /// Deals the hands the duel started with again, under the same rules.
+    fn restart_duel(&mut self) {
+        let Some(setup) = &self.duel_setup else {
+            return;
+        };
+        self.play.rules = setup.rules.clone();
+        self.play.clear();
+        self.play.opponent.reset();
+        self.play.red_hand = Hand::from_ids(
+            Suit::Red,
+            false,
+            &setup.red,
+            &self.play.card_atlas,
+            &self.play.sprite_sheet,
+        );
+        self.play.blue_hand = Hand::from_ids(
+            Suit::Blue,
+            true,
+            &setup.blue,
+            &self.play.card_atlas,
+            &self.play.sprite_sheet,
+        );
+        self.state_stack.set(&[State::Play, State::CoinFlip]);
+    }
+
+    fn handle_pause(&mut self, event: Event, events: &mut EventBus<Event>) {
+        let sfx = match event {
+            Event::Finished => {
+                self.state_stack.pop();
+                Sfx::Cancel
+            }
+            Event::Restart => {
+                self.restart_duel();
+                Sfx::Select
+            }
+            // A conceded duel is lost whatever the score.
+            Event::Concede => {
+                self.state_stack.pop();
+                self.finish(DuelOutcome::Lose);
+                Sfx::Select
+            }
+            Event::EditSettings => {
+                self.state_stack.push(State::Settings);
+                Sfx::Select
+            }
+            Event::ReturnToMenu => {
+                SuspendedDuel::remove(&self.suspend_dir);
+                self.campaign_match = None;
+                events.publish(Event::Finished);
+                return;
+            }
+            _ => unreachable!(),
+        };
+        events.publish(Event::PlaySound(sfx));
+    }
+
+    /// Saves the duel in progress, so that it can be continued after the
+    /// game is closed.
+    fn suspend_duel(&self) {
+        let Some(setup) = &self.duel_setup else {
+            return;
+        };
+        let card_atlas = &self.play.card_atlas;
+        let keys = |ids: &[usize]| -> Vec<String> {
+            ids.iter().map(|id| card_atlas.cards[*id].key()).collect()
+        };
+        let duel = SuspendedDuel {
+            board: self.play.suspend(),
+            campaign: self
+                .campaign_match
+                .map(|n| self.campaign.opponents[n].id.clone()),
+            profile: self.profile.map(|n| self.roster.profiles[n].id.clone()),
+            setup_rules: setup.rules.clone(),
+            setup_red: keys(&setup.red),
+            setup_blue: keys(&setup.blue),
+        };
+        if let Err(e) = duel.save(&self.suspend_dir) {
+            eprintln!("{e}");
+        }
+    }
+
+    /// Puts the suspended duel back on the board, at the start of the turn
+    /// it was left on.
+    fn continue_duel(&mut self, events: &mut EventBus<Event>) {
+        let Some(duel) = self.suspended.take() else {
+            return;
+        };
+        if let Err(e) = self.play.resume(&duel.board) {
+            eprintln!("{SUSPEND_FILE}: {e}");
+            SuspendedDuel::remove(&self.suspend_dir);
+            self.play.init();
+            self.state_stack.pop();
+            events.publish(Event::PlaySound(Sfx::Cancel));
+            return;
+        }
+        let card_atlas = &self.play.card_atlas;
+        let ids = |keys: &[String]| -> Vec<usize> {
+            keys.iter()
+                .filter_map(|key| card_atlas.position(key))
+                .collect()
+        };
+        self.duel_setup = Some(DuelSetup {
+            rules: duel.setup_rules,
+            red: ids(&duel.setup_red),
+            blue: ids(&duel.setup_blue),
+        });
+        self.campaign_match = duel.campaign.and_then(|id| {
+            self.campaign
+                .opponents
+                .iter()
+                .position(|opponent| opponent.id == id)
+        });
+        self.profile = duel.profile.and_then(|id| {
+            self.roster
+                .profiles
+                .iter()
+                .position(|profile| profile.id == id)
+        });
+        self.state_stack.set(&[State::Play]);
+        events.publish(Event::PlaySound(Sfx::Select));
+    }
+
+    fn handle_menu(&mut self, event: Event, events: &mut EventBus<Event>) {
+        let sfx = match event {
+            Event::Play => {
+                let red_hand = match self.profile {
+                    Some(n) => self.roster.profiles[n].deck.deal(&self.play.card_atlas),
+                    None => self.play.opponent.new_hand(),
+                };
+                self.start_duel(&red_hand);
+                Sfx::Select
+            }
+            Event::ChangeOpponent => {
+                self.profile = match self.profile {
+                    None if !self.roster.profiles.is_empty() => Some(0),
+                    Some(n) if n + 1 < self.roster.profiles.len() => Some(n + 1),
+                    _ => None,
+                };
+                Sfx::Select
+            }
+            Event::Quit => {
+                self.state_stack.clear();
+                Sfx::Cancel
+            }
+            Event::ChangeRule(r) => {
+                self.play.toggle_rule(r);
+                Sfx::Select
+            }
+            Event::ChangeDifficulty => {
+                let curr = self.play.opponent.difficulty();
+                let d = if curr >= 3 { 1 } else { curr + 1 };
+                self.play.opponent.set_difficulty(d);
+                Sfx::Select
+            }
+            Event::EditDecks => {
+                self.state_stack.replace(State::DeckBuilder);
+                Sfx::Select
+            }
+            Event::OpenCampaign => {
+                self.state_stack.replace(State::Campaign);
+                Sfx::Select
+            }
+            Event::OpenStats => {
+                self.state_stack.replace(State::Stats);
+                Sfx::Select
+            }
+            Event::OpenAlbum => {
+                self.state_stack.replace(State::Album);
+                Sfx::Select
+            }
+            Event::EditSettings => {
+                self.state_stack.replace(State::Settings);
+                Sfx::Select
+            }
+            Event::ToggleCards(n) => {
+                self.play.opponent.toogle_cards(n);
+                Sfx::Select
+            }
+            _ => unreachable!(),
+        };
+        events.publish(Event::PlaySound(sfx));
     }
 
     pub fn draw(
@@ -217,29 +876,42 @@ impl PlayState {
         array: &mut InstanceArray,
         elem_array: &mut InstanceArray,
     ) {
-        for state in &self.state_stack {
+        for state in self.state_stack.iter() {
             match state {
                 State::Menu => self.menu.draw(ctx, array, canvas),
+                State::DeckBuilder => self.deck_builder.draw(ctx, canvas, array),
+                State::Album => {
+                    self.album
+                        .draw(ctx, canvas, array, &self.collection, &self.holders);
+                }
+                State::Campaign => {
+                    self.campaign_map
+                        .draw(canvas, &self.campaign, &self.progress);
+                }
+                State::Stats => self.stats.draw(canvas, &self.profiles),
+                State::Settings => self.settings.draw(canvas),
                 State::CardPick => self.card_pick.draw(ctx, canvas, array),
+                // Only shown once it is picking a side.
+                State::CoinFlip if self.state_stack.last() == Some(&State::CoinFlip) => {
+                    self.coin_flip.draw(canvas, array);
+                }
                 State::CoinFlip => {}
                 State::Play => self.play.draw(ctx, canvas, array, elem_array),
+                State::Pause => self.pause.draw(canvas),
+                State::Resume => self.resume.draw(canvas),
                 State::Fin => self.fin.draw(canvas, array),
             }
         }
 
-        //self.draw_state_stack(canvas);
+        self.toast.draw(canvas);
     }
 
-    // fn draw_state_stack(&self, canvas: &mut Canvas) {
-    //     let text_raw = self
-    //         .state_stack
-    //         .iter()
-    //         .rev()
-    //         .map(|state| format!("{state:?}\n"))
-    //         .collect::<String>();
-
-    //     let text = Text::new(text_raw);
-    //     let text_pos = [100., 520.];
-    //     canvas.draw(&text, DrawParam::default().dest(text_pos));
-    // }
+    /// The screens of the play state and of the board, the active one first.
+    pub fn describe(&self) -> String {
+        format!(
+            "{}--\n{}",
+            self.state_stack.describe(),
+            self.play.describe()
+        )
+    }
 }
@@ -1,8 +1,10 @@
 use rand::seq::SliceRandom;
 
-use crate::core::Rules;
+use std::rc::Rc;
+
+use crate::core::{CardAtlas, Rules};
 use crate::graphics::{CardEntity, ElementEntity};
-use crate::state::play_state::ai::Opponent as Ai;
+use crate::state::play_state::ai::{self, Opponent as Ai};
 use crate::state::play_state::Hand;
 
 pub enum AiEvent {
@@ -11,15 +13,24 @@ pub enum AiEvent {
 }
 
 pub struct Opponent {
-    card_enabled: [bool; 10],
+    levels: Vec<u8>,
+    card_enabled: Vec<bool>,
     ai: Ai,
+    default_difficulty: usize,
+    think_delay: f32,
+    card_atlas: Rc<CardAtlas>,
 }
 
 impl Opponent {
-    pub fn new() -> Self {
+    pub fn new(card_atlas: &Rc<CardAtlas>) -> Self {
+        let levels = card_atlas.levels();
         Self {
-            card_enabled: [true; 10],
+            card_enabled: vec![true; levels.len()],
+            levels,
             ai: Ai::new(),
+            default_difficulty: 1,
+            think_delay: ai::TIMEOUT,
+            card_atlas: Rc::clone(card_atlas),
         }
     }
     pub fn toogle_cards(&mut self, n: usize) {
@@ -32,17 +43,51 @@ impl Opponent {
     pub const fn difficulty(&self) -> usize {
         self.ai.diffuculty
     }
-    pub const fn cards(&self) -> [bool; 10] {
-        self.card_enabled
+    pub fn cards(&self) -> &[bool] {
+        &self.card_enabled
     }
 
     pub fn set_difficulty(&mut self, value: usize) {
         self.ai.diffuculty(value);
     }
 
+    /// Sets the difficulty new matches start with and how long the AI waits
+    /// before each move. A value that changed also applies to the current
+    /// match, so a difficulty picked in the menu survives other settings.
+    pub fn apply_settings(&mut self, difficulty: usize, think_delay: f32) {
+        if self.default_difficulty != difficulty {
+            self.default_difficulty = difficulty;
+            self.ai.diffuculty(difficulty);
+        }
+        if self.think_delay != think_delay {
+            self.think_delay = think_delay;
+            self.ai.timeout = think_delay;
+        }
+    }
+
+    /// How long the AI waits before each move in the current match.
+    pub const fn think_delay(&self) -> f32 {
+        self.ai.timeout
+    }
+
+    /// Overrides the think delay for the current match.
+    pub fn set_think_delay(&mut self, think_delay: f32) {
+        self.ai.timeout = think_delay;
+    }
+
+    /// Drops the move being thought about, the difficulty and delay stay.
+    pub fn reset(&mut self) {
+        let (difficulty, timeout) = (self.ai.diffuculty, self.ai.timeout);
+        self.ai = Ai::new();
+        self.ai.diffuculty(difficulty);
+        self.ai.timeout = timeout;
+    }
+
     pub fn clear(&mut self) {
         self.ai = Ai::new();
-        self.card_enabled = [true; 10];
+        self.ai.diffuculty(self.default_difficulty);
+        self.ai.timeout = self.think_delay;
+        self.card_enabled = vec![true; self.levels.len()];
     }
 
     pub fn think(
@@ -58,17 +103,20 @@ impl Opponent {
     }
 
     pub fn new_hand(&self) -> [usize; 5] {
-        let mut cards = Vec::with_capacity(110);
+        let cards: Vec<usize> = self
+            .card_atlas
+            .cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| {
+                self.levels
+                    .iter()
+                    .zip(&self.card_enabled)
+                    .any(|(lvl, enabled)| *enabled && *lvl == card.level)
+            })
+            .map(|(id, _)| id)
+            .collect();
 
-        for (i, lvl) in self.card_enabled.iter().enumerate() {
-            if *lvl {
-                let lower_bound = i * 11;
-                let upper_bound = (i + 1) * 11;
-                for j in lower_bound..upper_bound {
-                    cards.push(j);
-                }
-            }
-        }
         let ids: [usize; 5] =
             std::array::from_fn(|_| *cards.choose(&mut rand::thread_rng()).unwrap());
         ids
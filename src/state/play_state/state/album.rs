@@ -0,0 +1,330 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, DrawParam, InstanceArray, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::card_selector::PageNavButton;
+use super::menu::MenuItem;
+use crate::collection::Collection;
+use crate::consts::{CARD_SIZE, FONT_SIZE};
+use crate::core::{next_element, next_level, Card, CardAtlas, Element, Suit, RARE_LEVEL};
+use crate::events::EventBus;
+use crate::graphics::text::{draw_label, row};
+use crate::graphics::{sprite::Atlas, CardEntity, TextBox};
+use crate::layout;
+use crate::utils::{Event, Sfx};
+
+const COLUMNS: usize = 5;
+const CARDS_PER_PAGE: usize = 10;
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+
+const FILTERS_Y: f32 = 15.0;
+const LEVEL_FILTER_X: f32 = 20.0;
+const ELEMENT_FILTER_X: f32 = 210.0;
+const SORT_X: f32 = 460.0;
+const BACK_BUTTON_X: f32 = 700.0;
+
+const GRID_POS: [f32; 2] = [20.0, 55.0];
+const GRID_STEP: [f32; 2] = [155.0, 180.0];
+
+const NAV_BTNS_Y: f32 = 428.0;
+const PREV_BTN_X: f32 = 300.0;
+const NEXT_BTN_X: f32 = 480.0;
+const PAGE_LABEL_POS: [f32; 2] = [370.0, 415.0];
+
+const INFO_POS: [f32; 2] = [20.0, 450.0];
+const INFO_DIMENSIONS: [f32; 2] = [760.0, 135.0];
+
+const LEVEL_FILTER: usize = 0;
+const ELEMENT_FILTER: usize = 1;
+const SORT: usize = 2;
+const BACK_BUTTON: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sort {
+    Number,
+    RankSum,
+}
+
+/// Browses every card with what the player knows about it.
+///
+/// The filters at the top cycle on click: level, element (including cards
+/// without one) and the sort order.
+pub struct Album {
+    info_bg: TextBox,
+    buttons: Vec<MenuItem>,
+    next_btn: PageNavButton,
+    prev_btn: PageNavButton,
+    levels: Vec<u8>,
+    level: Option<u8>,
+    /// `Some(None)` keeps the cards without an element.
+    element: Option<Option<Element>>,
+    sort: Sort,
+    cards: Vec<usize>,
+    page: usize,
+    slots: Vec<CardEntity>,
+    hovered: Option<usize>,
+    card_atlas: Rc<CardAtlas>,
+    sprite_sheet: Rc<Atlas>,
+}
+
+impl Album {
+    pub fn new(ctx: &mut Context, card_atlas: &Rc<CardAtlas>, sprite_sheet: &Rc<Atlas>) -> Self {
+        let buttons = [LEVEL_FILTER_X, ELEMENT_FILTER_X, SORT_X, BACK_BUTTON_X]
+            .iter()
+            .map(|x| MenuItem {
+                label: String::new(),
+                disabled: false,
+                rect: Rect::new(*x, FILTERS_Y, 0.0, FONT_SIZE),
+                callback: None,
+            })
+            .collect();
+
+        let mut res = Self {
+            info_bg: TextBox::new(ctx, INFO_POS, INFO_DIMENSIONS),
+            buttons,
+            next_btn: PageNavButton::new_right(sprite_sheet).at(NEXT_BTN_X, NAV_BTNS_Y),
+            prev_btn: PageNavButton::new_left(sprite_sheet).at(PREV_BTN_X, NAV_BTNS_Y),
+            levels: card_atlas.levels(),
+            level: None,
+            element: None,
+            sort: Sort::Number,
+            cards: Vec::new(),
+            page: 0,
+            slots: Vec::with_capacity(CARDS_PER_PAGE),
+            hovered: None,
+            card_atlas: Rc::clone(card_atlas),
+            sprite_sheet: Rc::clone(sprite_sheet),
+        };
+        res.update_cards();
+        res
+    }
+
+    pub fn init(&mut self) {
+        self.page = 0;
+        self.hovered = None;
+        self.update_cards();
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, events: &mut EventBus<Event>) {
+        let mouse = layout::mouse_position(ctx);
+
+        self.hovered = self
+            .slots
+            .iter()
+            .find(|card| {
+                Rect::new(card.pos.x, card.pos.y, CARD_SIZE[0], CARD_SIZE[1]).contains(mouse)
+            })
+            .map(|card| card.id);
+
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            events.publish(Event::Finished);
+            return;
+        }
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let pages = self.pages();
+        if self.next_btn.rect.contains(mouse) {
+            self.page = (self.page + 1) % pages;
+            self.update_slots();
+            events.publish(Event::PlaySound(Sfx::Move));
+            return;
+        }
+        if self.prev_btn.rect.contains(mouse) {
+            self.page = (self.page + pages - 1) % pages;
+            self.update_slots();
+            events.publish(Event::PlaySound(Sfx::Move));
+            return;
+        }
+
+        match self
+            .buttons
+            .iter()
+            .position(|item| item.rect.contains(mouse))
+        {
+            Some(LEVEL_FILTER) => self.level = next_level(&self.levels, self.level),
+            Some(ELEMENT_FILTER) => self.element = next_element(self.element),
+            Some(SORT) => {
+                self.sort = match self.sort {
+                    Sort::Number => Sort::RankSum,
+                    Sort::RankSum => Sort::Number,
+                };
+            }
+            Some(BACK_BUTTON) => {
+                events.publish(Event::Finished);
+                return;
+            }
+            _ => return,
+        }
+        self.page = 0;
+        self.update_cards();
+        events.publish(Event::PlaySound(Sfx::Select));
+    }
+
+    fn pages(&self) -> usize {
+        self.cards.len().div_ceil(CARDS_PER_PAGE).max(1)
+    }
+
+    fn update_cards(&mut self) {
+        let matches = |card: &Card| {
+            self.level.map_or(true, |level| card.level == level)
+                && self.element.map_or(true, |element| card.element == element)
+        };
+        self.cards = self
+            .card_atlas
+            .cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| matches(card))
+            .map(|(id, _)| id)
+            .collect();
+        if self.sort == Sort::RankSum {
+            let cards = &self.card_atlas.cards;
+            self.cards
+                .sort_by_key(|id| std::cmp::Reverse(rank_sum(&cards[*id])));
+        }
+
+        let labels = [
+            self.level
+                .map_or_else(|| "Level: All".into(), |level| format!("Level: {level}")),
+            match self.element {
+                None => "Element: All".into(),
+                Some(None) => "Element: None".into(),
+                Some(Some(element)) => format!("Element: {element:?}"),
+            },
+            match self.sort {
+                Sort::Number => "Sort: Number".into(),
+                Sort::RankSum => "Sort: Ranks".into(),
+            },
+            "Back".into(),
+        ];
+        for (item, label) in self.buttons.iter_mut().zip(labels) {
+            item.rect.w = f32::from(u8::try_from(label.len()).expect("Value is too big")) * 12.0;
+            item.label = label;
+        }
+        self.update_slots();
+    }
+
+    fn update_slots(&mut self) {
+        self.slots = self
+            .cards
+            .iter()
+            .skip(self.page * CARDS_PER_PAGE)
+            .take(CARDS_PER_PAGE)
+            .enumerate()
+            .map(|(n, id)| {
+                CardEntity::new(
+                    *id,
+                    [
+                        GRID_STEP[0].mul_add(row(n % COLUMNS), GRID_POS[0]),
+                        GRID_STEP[1].mul_add(row(n / COLUMNS), GRID_POS[1]),
+                    ]
+                    .into(),
+                    Suit::Blue,
+                    false,
+                    &self.card_atlas,
+                    &self.sprite_sheet,
+                )
+            })
+            .collect();
+    }
+
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        array: &mut InstanceArray,
+        collection: &Collection,
+        holders: &HashMap<String, Vec<String>>,
+    ) {
+        for item in &self.buttons {
+            item.draw(canvas);
+        }
+        draw_label(
+            canvas,
+            &format!("{}/{}", self.page + 1, self.pages()),
+            PAGE_LABEL_POS,
+        );
+        self.next_btn.draw(ctx, canvas, array);
+        self.prev_btn.draw(ctx, canvas, array);
+
+        self.info_bg.draw(canvas);
+        let line = |n: f32| {
+            [
+                INFO_POS[0] + 15.0,
+                LINE_HEIGHT.mul_add(n, INFO_POS[1] + 12.0),
+            ]
+        };
+        match self.hovered.map(|id| &self.card_atlas.cards[id]) {
+            Some(card) => {
+                let key = card.key();
+                let record = collection.get(&key);
+                let status = if record.captured > 0 {
+                    "Captured"
+                } else if record.seen > 0 {
+                    "Seen"
+                } else {
+                    "Not seen yet"
+                };
+                let [top, right, bottom, left] = card.rank_as_slice().map(rank_label);
+                let element = card
+                    .element
+                    .map_or_else(|| "None".into(), |element| format!("{element:?}"));
+                let rare = if card.level >= RARE_LEVEL {
+                    " - Rare"
+                } else {
+                    ""
+                };
+                draw_label(
+                    canvas,
+                    &format!("{} - Level {}{rare} - {status}", card.name, card.level),
+                    line(0.0),
+                );
+                draw_label(
+                    canvas,
+                    &format!("Ranks: {top} {right} {bottom} {left}   Element: {element}"),
+                    line(1.0),
+                );
+                draw_label(
+                    canvas,
+                    &format!(
+                        "Owned: {}   Seen: {}   Captured: {}",
+                        record.owned, record.seen, record.captured
+                    ),
+                    line(2.0),
+                );
+                if let Some(names) = holders.get(&key) {
+                    draw_label(canvas, &format!("Held by: {}", names.join(", ")), line(3.0));
+                }
+            }
+            None if self.cards.is_empty() => draw_label(canvas, "No card matches", line(0.0)),
+            None => draw_label(canvas, "Point at a card for details", line(0.0)),
+        }
+
+        array.clear();
+        for card in &self.slots {
+            card.add_to_instance_array(array);
+        }
+        canvas.draw(array, DrawParam::default());
+    }
+}
+
+fn rank_sum(card: &Card) -> u16 {
+    card.rank_as_slice()
+        .iter()
+        .map(|rank| u16::from(*rank))
+        .sum()
+}
+
+fn rank_label(rank: u8) -> String {
+    if rank >= 10 {
+        "A".into()
+    } else {
+        rank.to_string()
+    }
+}
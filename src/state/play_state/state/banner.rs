@@ -1,12 +1,14 @@
 use ggez::event::MouseButton;
-use ggez::graphics::{Canvas, DrawParam, InstanceArray};
+use ggez::graphics::{Canvas, Color, DrawParam, InstanceArray, TextAlign, TextLayout};
 use ggez::Context;
 
 use std::rc::Rc;
 
 use crate::consts;
 use crate::core::DuelOutcome;
+use crate::events::EventBus;
 use crate::graphics::sprite::Atlas as SpriteAtlas;
+use crate::graphics::text::draw_text;
 use crate::utils::{Event, Sfx};
 
 const SCALE_FACTOR: f32 = consts::SCALE_FACTOR * 0.75;
@@ -15,6 +17,8 @@ pub struct Banner {
     _timer: f32,
     started: bool,
     pub outcome: DuelOutcome,
+    /// Sudden Death rounds the match took.
+    pub rounds: u32,
     sprite_sheet: Rc<SpriteAtlas>,
 }
 
@@ -23,6 +27,7 @@ impl Banner {
         self._timer = 0.0;
         self.started = false;
         self.outcome = DuelOutcome::Draw;
+        self.rounds = 0;
     }
 
     pub fn new(sprite_sheet: &Rc<SpriteAtlas>) -> Self {
@@ -30,21 +35,21 @@ impl Banner {
             _timer: 0.0,
             started: false,
             outcome: DuelOutcome::Draw,
+            rounds: 0,
             sprite_sheet: Rc::clone(sprite_sheet),
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context) -> Option<Event> {
+    pub fn update(&mut self, ctx: &mut Context, events: &mut EventBus<Event>) {
         if !self.started {
             self.started = true;
-            if matches!(self.outcome, DuelOutcome::Win) {
-                return Some(Event::PlaySound(Sfx::Fanfare));
+            if matches!(self.outcome, DuelOutcome::Lose) {
+                events.publish(Event::PlaySound(Sfx::Defeat));
             }
         }
         if ctx.mouse.button_just_pressed(MouseButton::Left) {
-            return Some(Event::Finished);
+            events.publish(Event::Finished);
         }
-        None
     }
 
     pub fn draw(&self, canvas: &mut Canvas, array: &mut InstanceArray) {
@@ -66,5 +71,18 @@ impl Banner {
                 ]),
         );
         canvas.draw(array, [0.0, 0.0]);
+        if self.rounds > 0 {
+            let plural = if self.rounds == 1 { "" } else { "s" };
+            let text = format!("after {} Sudden Death round{plural}", self.rounds);
+            let pos = [
+                consts::WINDOW_DIMENSIONS[0] / 2.0,
+                (consts::WINDOW_DIMENSIONS[1] + sprite.height * SCALE_FACTOR) / 2.0 + 10.0,
+            ];
+            let layout = TextLayout {
+                h_align: TextAlign::Middle,
+                v_align: TextAlign::Begin,
+            };
+            draw_text(canvas, &text, pos, Color::WHITE, layout);
+        }
     }
 }
@@ -0,0 +1,189 @@
+use ggez::graphics::{Canvas, Color, DrawParam, Image, Rect, TextAlign, TextLayout};
+use ggez::Context;
+
+use crate::graphics::text::draw_text;
+
+/// Seconds the overlay stays up after the last check of a move.
+const DURATION: f32 = 2.5;
+const BAR_THICKNESS: f32 = 6.0;
+const BAR_INSET: f32 = 12.0;
+
+/// The rule that flipped the neighbour across an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Normal,
+    Same,
+    Plus,
+}
+
+/// One edge of a checked card and what it was compared with.
+#[derive(Debug, Clone)]
+pub struct Edge {
+    /// Of the checked card: 0 top, 1 right, 2 bottom, 3 left.
+    pub side: usize,
+    pub own: u8,
+    /// Elemental bonus or malus of the checked card, -1 to 1.
+    pub own_effect: i16,
+    /// 10 for a wall.
+    pub other: u8,
+    pub other_effect: i16,
+    /// The board edge, only shown when Same Wall counted it.
+    pub wall: bool,
+    /// `None` if the neighbour held.
+    pub reason: Option<Reason>,
+}
+
+impl Edge {
+    fn label(&self) -> String {
+        match self.reason {
+            Some(Reason::Same) if self.wall => format!("{}=Wall", rank(self.own, 0)),
+            Some(Reason::Same) => format!("{}={}", rank(self.own, 0), rank(self.other, 0)),
+            Some(Reason::Plus) => format!(
+                "{}+{}={}",
+                rank(self.own, 0),
+                rank(self.other, 0),
+                self.own + self.other
+            ),
+            Some(Reason::Normal) => format!(
+                "{}>{}",
+                rank(self.own, self.own_effect),
+                rank(self.other, self.other_effect)
+            ),
+            None => format!(
+                "{}<={}",
+                rank(self.own, self.own_effect),
+                rank(self.other, self.other_effect)
+            ),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.reason {
+            Some(Reason::Normal) => Color::from_rgb(255, 255, 255),
+            Some(Reason::Same) => Color::from_rgb(255, 215, 80),
+            Some(Reason::Plus) => Color::from_rgb(110, 230, 120),
+            None => Color::from_rgb(130, 130, 130),
+        }
+    }
+}
+
+/// Shows why cards flipped: the edges each checked card was compared on,
+/// with the ranks, sums and walls that decided it.
+pub struct CaptureOverlay {
+    pub enabled: bool,
+    /// The cell of each checked card and its edges.
+    checks: Vec<(Rect, Vec<Edge>)>,
+    timer: f32,
+    bar: Image,
+}
+
+impl CaptureOverlay {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self {
+            enabled: false,
+            checks: Vec::new(),
+            timer: 0.0,
+            bar: Image::from_solid(ctx, 1, Color::WHITE),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.checks.clear();
+    }
+
+    /// Adds the edges of the card checked in `cell`, if any were compared.
+    pub fn add(&mut self, cell: Rect, edges: Vec<Edge>) {
+        if !self.enabled || edges.is_empty() {
+            return;
+        }
+        self.checks.push((cell, edges));
+        self.timer = 0.0;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.checks.is_empty() {
+            return;
+        }
+        self.timer += dt;
+        if self.timer >= DURATION {
+            self.checks.clear();
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        for (cell, edges) in &self.checks {
+            for edge in edges {
+                let (bar, pos) = edge_geometry(*cell, edge.side);
+                canvas.draw(
+                    &self.bar,
+                    DrawParam::default()
+                        .dest([bar.x, bar.y])
+                        .scale([bar.w, bar.h])
+                        .color(edge.color()),
+                );
+                let layout = TextLayout {
+                    h_align: TextAlign::Middle,
+                    v_align: TextAlign::Middle,
+                };
+                draw_text(canvas, &edge.label(), pos, edge.color(), layout);
+            }
+        }
+    }
+}
+
+/// The bar along an edge of `cell` and the middle of that edge.
+fn edge_geometry(cell: Rect, side: usize) -> (Rect, [f32; 2]) {
+    let half = BAR_THICKNESS / 2.0;
+    match side {
+        0 => (
+            Rect::new(
+                cell.x + BAR_INSET,
+                cell.y - half,
+                cell.w - 2.0 * BAR_INSET,
+                BAR_THICKNESS,
+            ),
+            [cell.x + cell.w / 2.0, cell.y],
+        ),
+        1 => (
+            Rect::new(
+                cell.x + cell.w - half,
+                cell.y + BAR_INSET,
+                BAR_THICKNESS,
+                cell.h - 2.0 * BAR_INSET,
+            ),
+            [cell.x + cell.w, cell.y + cell.h / 2.0],
+        ),
+        2 => (
+            Rect::new(
+                cell.x + BAR_INSET,
+                cell.y + cell.h - half,
+                cell.w - 2.0 * BAR_INSET,
+                BAR_THICKNESS,
+            ),
+            [cell.x + cell.w / 2.0, cell.y + cell.h],
+        ),
+        _ => (
+            Rect::new(
+                cell.x - half,
+                cell.y + BAR_INSET,
+                BAR_THICKNESS,
+                cell.h - 2.0 * BAR_INSET,
+            ),
+            [cell.x, cell.y + cell.h / 2.0],
+        ),
+    }
+}
+
+/// A rank as printed on the cards, with its elemental change.
+fn rank(value: u8, effect: i16) -> String {
+    let value = if value == 10 {
+        "A".into()
+    } else {
+        value.to_string()
+    };
+    match effect {
+        0 => value,
+        e if e > 0 => format!("{value}+{e}"),
+        e => format!("{value}{e}"),
+    }
+}
@@ -50,7 +50,6 @@ impl Hand {
                 *id,
                 [pos_x, consts::WINDOW_DIMENSIONS[1] + 150.0].into(),
                 side,
-                side,
                 !open,
                 card_atlas,
                 sprite_sheet,
@@ -136,7 +135,6 @@ impl Hand {
             id,
             [pos_x, consts::WINDOW_DIMENSIONS[1] + 150.0].into(),
             self.side,
-            self.side,
             !open,
             &self.card_atlas,
             &self.sprite_sheet,
@@ -145,30 +143,92 @@ impl Hand {
         self.cards[n as usize] = Some(card_entity);
     }
 
-    pub fn add_card_entity(&mut self, mut card: CardEntity) {
+    /// Atlas indices of the cards in the hand, in slot order.
+    pub fn card_ids(&self) -> Vec<usize> {
+        self.cards.iter().flatten().map(|card| card.id).collect()
+    }
+
+    /// Number of cards held, picked cards always fill the first slots.
+    pub fn card_count(&self) -> usize {
+        self.cards.iter().flatten().count()
+    }
+
+    /// Takes the card in slot `n` out, the cards below it move up a slot.
+    pub fn remove_card(&mut self, n: usize) {
+        self.reset_foucus();
+        self.cards[n] = None;
+        self.cards[n..].rotate_left(1);
+        self.arrange();
+    }
+
+    pub fn swap_cards(&mut self, a: usize, b: usize) {
+        self.reset_foucus();
+        self.cards.swap(a, b);
+        self.arrange();
+    }
+
+    pub fn clear_cards(&mut self) {
+        self.focus = None;
+        self.selected = None;
+        self.cards = [None, None, None, None, None];
+    }
+
+    /// Puts every card back at the height of its slot, a card still being
+    /// dealt is dealt to its new slot.
+    fn arrange(&mut self) {
+        let offset = match &self.side {
+            Suit::Red => consts::LEFT_HAND_OFFSET,
+            Suit::Blue => consts::RIGHT_HAND_OFFSET,
+        };
+        for (n, card) in self.cards.iter_mut().enumerate() {
+            let Some(card) = card else {
+                continue;
+            };
+            let n_small = u8::try_from(n).expect("Value is too big");
+            let pos_y = consts::HAND_STEP.mul_add(f32::from(n_small), offset[1]);
+            if card.deal_animation_finished() {
+                card.pos.y = pos_y;
+            } else {
+                card.start_deal_animation(pos_y);
+            }
+        }
+    }
+
+    pub fn add_card_entity(&mut self, card: CardEntity) {
         if let Some((n, empty)) = self
             .cards
             .iter_mut()
             .enumerate()
             .find(|(_, maybe_card)| maybe_card.is_none())
         {
-            let n_small = u8::try_from(n).expect("Value is too big: {n}");
-            let offset = match &self.side {
-                Suit::Red => consts::LEFT_HAND_OFFSET,
-                Suit::Blue => consts::RIGHT_HAND_OFFSET,
-            };
-            let pos_x = offset[0];
-            let pos_y = consts::HAND_STEP.mul_add(f32::from(n_small), offset[1]);
+            *empty = Some(card);
+            self.settle(n);
+            return;
+        }
+        panic!("Hand is full!");
+    }
+
+    /// Puts a card at rest in slot `n`, without dealing it.
+    pub fn set_card_entity(&mut self, n: usize, card: CardEntity) {
+        self.cards[n] = Some(card);
+        self.settle(n);
+    }
+
+    fn settle(&mut self, n: usize) {
+        let n_small = u8::try_from(n).expect("Value is too big: {n}");
+        let offset = match &self.side {
+            Suit::Red => consts::LEFT_HAND_OFFSET,
+            Suit::Blue => consts::RIGHT_HAND_OFFSET,
+        };
+        let pos_x = offset[0];
+        let pos_y = consts::HAND_STEP.mul_add(f32::from(n_small), offset[1]);
+        if let Some(card) = &mut self.cards[n] {
             card.pos = [pos_x, pos_y].into();
             card.focused = false;
             card.adjust_focus_tween();
             card.reset_focus_tweens();
-            self.selected = None;
-            *empty = Some(card);
-
-            return;
         }
-        panic!("Hand is full!");
+        self.selected = None;
     }
     
     pub const fn focus(&self) -> Option<usize>{
@@ -1,16 +1,24 @@
 use ggez::graphics::{
-    Canvas, Color, DrawParam, Image, InstanceArray, PxScale, Rect, Text, TextFragment,
+    Canvas, Color, DrawParam, Image, InstanceArray, PxScale, Rect, Text, TextAlign, TextFragment,
+    TextLayout,
 };
 use ggez::Context;
 
 use mint::Point2;
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
+use crate::config::Gameplay;
 use crate::consts;
 use crate::core::{CardAtlas, DuelOutcome, Element, Rules, Suit};
+use crate::events::{Capture, EventBus, GameEvent, Subscriber};
+use crate::layout;
+use crate::profile::Move;
 use crate::utils::{self, Event, Rule as SpecialRule, Sfx};
 
+use crate::graphics::text::draw_text;
 use crate::graphics::{
     sprite::Atlas as SpriteAtlas, CardEntity, ElementEntity, ElementalEffect, TextBox,
 };
@@ -18,9 +26,14 @@ use crate::graphics::{
 //use super::super::GameSummary;
 
 use crate::state::play_state::opponent::{AiEvent, Opponent};
+use crate::state::play_state::suspend::{SavedBoard, SavedCard};
 
+mod explain;
 mod hand;
+mod score;
+use explain::{CaptureOverlay, Edge, Reason};
 pub use hand::Hand;
+use score::Score;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum TurnPhase {
@@ -44,7 +57,7 @@ enum State {
 #[derive(Clone, Debug)]
 pub struct CardFlipped {
     pub cell_id: usize,
-    pub combo: Combo,
+    pub combo: Capture,
 }
 struct PlayingField {
     cards: [Option<CardEntity>; 9],
@@ -59,19 +72,78 @@ impl PlayingField {
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum Combo {
-    None,
-    Plus,
-    Same,
-    Combo,
+/// What happened during the current match, for the player profile.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchStats {
+    /// Combos the player triggered.
+    pub same: u32,
+    pub plus: u32,
+    pub combo: u32,
+    pub sudden_death_rounds: u32,
+    /// Most cards the player captured with one move, combos included.
+    pub best_chain: u32,
+    /// Every card put down, by key like the rest of a suspended duel.
+    pub moves: Vec<Move>,
+    /// Cards captured by the player's last move.
+    #[serde(skip)]
+    chain: u32,
+    /// The kinds of capture the last move triggered, Combo only for the
+    /// current step.
+    #[serde(skip)]
+    triggered: Vec<Capture>,
+}
+
+impl Subscriber for MatchStats {
+    fn notify(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::CardPlaced {
+                suit, card, cell, ..
+            } => {
+                self.moves.push(Move {
+                    suit: *suit,
+                    card: card.clone(),
+                    cell: *cell,
+                });
+                if *suit == Suit::Blue {
+                    self.chain = 0;
+                    self.triggered.clear();
+                }
+            }
+            GameEvent::CardCaptured {
+                suit: Suit::Blue,
+                by,
+                ..
+            } => {
+                self.chain += 1;
+                self.best_chain = self.best_chain.max(self.chain);
+                if self.triggered.contains(by) {
+                    return;
+                }
+                self.triggered.push(*by);
+                match by {
+                    Capture::Same => self.same += 1,
+                    Capture::Plus => self.plus += 1,
+                    Capture::Combo => self.combo += 1,
+                    Capture::Normal => {}
+                }
+            }
+            // Every Combo step counts, Same and Plus once per move.
+            GameEvent::ComboStep => self.triggered.retain(|by| *by != Capture::Combo),
+            GameEvent::MatchEnded {
+                outcome: DuelOutcome::Draw,
+                sudden_death: true,
+            } => self.sudden_death_rounds += 1,
+            _ => {}
+        }
+    }
 }
 
 struct ComboMessage {
     active: bool,
     timer: f32,
     duration: f32,
-    combo: Combo,
+    combo: Capture,
 }
 
 impl ComboMessage {
@@ -80,11 +152,11 @@ impl ComboMessage {
             active: false,
             timer: 0.0,
             duration: consts::COMBO_BANNER_DURATION,
-            combo: Combo::None,
+            combo: Capture::Normal,
         }
     }
 
-    pub fn start(&mut self, combo: Combo) {
+    pub fn start(&mut self, combo: Capture) {
         self.combo = combo;
         self.timer = 0.0;
         self.active = true;
@@ -106,10 +178,10 @@ impl ComboMessage {
         }
 
         let sprite_id = match self.combo {
-            Combo::Plus => consts::COMBO_PLUS_BANNER_SPRITE_ID,
-            Combo::Same => consts::COMBO_SAME_BANNER_SPRITE_ID,
-            Combo::Combo => consts::COMBO_BANNER_SPRITE_ID,
-            Combo::None => unreachable!(),
+            Capture::Plus => consts::COMBO_PLUS_BANNER_SPRITE_ID,
+            Capture::Same => consts::COMBO_SAME_BANNER_SPRITE_ID,
+            Capture::Combo => consts::COMBO_BANNER_SPRITE_ID,
+            Capture::Normal => unreachable!(),
         };
 
         let sprite = sprite_sheet.create_sprite(sprite_id);
@@ -134,6 +206,7 @@ struct Tooltip {
     bg_rect: TextBox,
     card_prev: Option<usize>,
     card_curr: Option<usize>,
+    delay: f32,
     card_atlas: Rc<CardAtlas>,
 }
 impl Tooltip {
@@ -155,6 +228,7 @@ impl Tooltip {
             bg_rect,
             card_prev: None,
             card_curr: None,
+            delay: consts::TOOLTIP_DELAY,
             card_atlas: Rc::clone(card_artlas),
         }
     }
@@ -167,7 +241,7 @@ impl Tooltip {
         self.timer = if self.card_prev == self.card_curr && self.card_curr.is_some() {
             0.0f32.max(self.timer - dt)
         } else {
-            consts::TOOLTIP_DELAY
+            self.delay
         };
         if self.timer == 0.0 {
             self.active = true;
@@ -212,9 +286,24 @@ pub struct Board {
     pub red_hand: Hand,
     pub blue_hand: Hand,
     pub rules: Rules,
+    default_rules: Rules,
+    /// Sudden Death rounds dealt so far, 0 during the regular round.
+    pub round: u32,
+    /// Sudden Death rounds dealt before a tie stands, 0 for no cap.
+    max_rounds: u32,
+    animation_speed: f32,
     pub opponent: Opponent,
     combo_message: ComboMessage,
+    score: Score,
+    capture_overlay: CaptureOverlay,
+    pub stats: MatchStats,
+    /// What happened this frame, dispatched by the play state.
+    pub events: EventBus,
+    /// Seed of the next random draw of the duel, see `rng`.
+    seed: u64,
     tooltip: Tooltip,
+    /// The steps of the turn being played, not screens, so it needs no
+    /// `SceneStack` hooks.
     state_stack: Vec<State>,
     pub card_atlas: Rc<CardAtlas>,
     pub sprite_sheet: Rc<SpriteAtlas>,
@@ -269,9 +358,18 @@ impl Board {
             red_hand: Hand::empty(Suit::Red, card_atlas, sprite_sheet),
             blue_hand: Hand::empty(Suit::Blue, card_atlas, sprite_sheet),
             rules: Rules::default(),
-            opponent: Opponent::new(),
+            default_rules: Rules::default(),
+            round: 0,
+            max_rounds: 0,
+            animation_speed: 1.0,
+            opponent: Opponent::new(card_atlas),
             state_stack,
             combo_message: ComboMessage::new(),
+            score: Score::new(sprite_sheet),
+            capture_overlay: CaptureOverlay::new(ctx),
+            stats: MatchStats::default(),
+            events: EventBus::default(),
+            seed: thread_rng().gen(),
             tooltip: Tooltip::new(ctx, card_atlas),
             card_atlas: Rc::clone(card_atlas),
             sprite_sheet: Rc::clone(sprite_sheet),
@@ -282,9 +380,14 @@ impl Board {
     pub fn init(&mut self) {
         self.playing_field.clear();
 
-        self.rules = Rules::default();
+        self.rules = self.default_rules.clone();
 
         self.opponent.clear();
+        self.stats = MatchStats::default();
+        self.score.reset();
+        self.capture_overlay.clear();
+        self.round = 0;
+        self.events.clear();
         self.state_stack = vec![
             State::Finish,
             State::RedPlayerTurn(TurnPhase::Pick),
@@ -293,7 +396,22 @@ impl Board {
         self.red_hand = Hand::empty(Suit::Red, &self.card_atlas, &self.sprite_sheet);
         self.blue_hand = Hand::empty(Suit::Blue, &self.card_atlas, &self.sprite_sheet);
     }
+    pub fn apply_settings(&mut self, gameplay: &Gameplay) {
+        if self.default_rules != gameplay.rules {
+            self.default_rules = gameplay.rules.clone();
+            self.rules = gameplay.rules.clone();
+        }
+        self.animation_speed = gameplay.animation_speed;
+        self.max_rounds = gameplay.max_sudden_death_rounds;
+        self.capture_overlay.enabled = gameplay.explain_captures;
+        self.tooltip.delay = gameplay.tooltip_delay;
+        self.opponent
+            .apply_settings(gameplay.difficulty, gameplay.ai_delay);
+    }
+
     pub fn first_turn(&mut self, p: Suit) {
+        self.events.publish(GameEvent::TurnStarted(p));
+        self.score.reset();
         self.state_stack.clear();
         self.state_stack.push(State::Finish);
         match p {
@@ -306,6 +424,12 @@ impl Board {
     }
     pub fn clear(&mut self) {
         self.playing_field.clear();
+        self.stats = MatchStats::default();
+        self.score.reset();
+        self.capture_overlay.clear();
+        self.round = 0;
+        self.events.clear();
+        self.seed = thread_rng().gen();
         if self.rules.elemental {
             self.populate_elem();
         }
@@ -320,24 +444,45 @@ impl Board {
         self.red_hand = Hand::empty(Suit::Blue, &self.card_atlas, &self.sprite_sheet);
     }
 
+    /// Deals another round after a tie. As in FF8, each side gets the cards
+    /// it controlled at the end, its own from then on.
     pub fn deal_sudden_death(&mut self) {
-        for maybe_card in &mut self.playing_field.cards {
-            match maybe_card.as_ref().unwrap().controller {
-                Suit::Red => self.red_hand.add_card_entity(maybe_card.take().unwrap()),
-                Suit::Blue => self.blue_hand.add_card_entity(maybe_card.take().unwrap()),
-            }
-        }
+        let cards: Vec<CardEntity> = self
+            .playing_field
+            .cards
+            .iter_mut()
+            .chain(&mut self.red_hand.cards)
+            .chain(&mut self.blue_hand.cards)
+            .filter_map(Option::take)
+            .collect();
 
         self.playing_field.clear();
+        self.red_hand = Hand::empty(Suit::Red, &self.card_atlas, &self.sprite_sheet);
+        self.blue_hand = Hand::empty(Suit::Blue, &self.card_atlas, &self.sprite_sheet);
+        for card in cards {
+            let side = card.controller;
+            // Rebuilt for the new hand, the opponent's cards are dealt face
+            // down like in the first round.
+            let card = CardEntity::new(
+                card.id,
+                card.pos,
+                side,
+                side == Suit::Red,
+                &self.card_atlas,
+                &self.sprite_sheet,
+            );
+            match side {
+                Suit::Red => self.red_hand.add_card_entity(card),
+                Suit::Blue => self.blue_hand.add_card_entity(card),
+            }
+        }
 
         if self.rules.elemental {
             self.populate_elem();
         }
-
-        self.red_hand.reset_foucus();
-        self.blue_hand.reset_foucus();
-        self.red_hand.clear_selected();
-        self.blue_hand.clear_selected();
+        self.score.reset();
+        self.capture_overlay.clear();
+        self.round += 1;
 
         self.state_stack.push(State::Start);
         self.state_stack.push(State::RedPlayerTurn(TurnPhase::Pick));
@@ -389,16 +534,35 @@ impl Board {
             self.state_stack.last().unwrap(),
             State::Start | State::WaitingPick
         ) {
-            self.draw_score(array);
+            self.score.draw_digits(array);
         }
         canvas.draw(array, [0.0, 0.0]);
         canvas.draw(elem_array, [0.0, 0.0]);
 
+        self.capture_overlay.draw(canvas);
+        self.score.draw_popups(canvas);
+        if let Some(label) = self.score.chain_label().or_else(|| self.round_label()) {
+            // A line of text centred above the board.
+            let layout = TextLayout {
+                h_align: TextAlign::Middle,
+                v_align: TextAlign::Begin,
+            };
+            let pos = [consts::WINDOW_DIMENSIONS[0] / 2.0, 12.0];
+            draw_text(canvas, &label, pos, Color::WHITE, layout);
+        }
         self.tooltip.draw(ctx, canvas);
-        // self.draw_state_stack(canvas);
         // self.draw_ai_state_stack(canvas);
     }
 
+    /// Which Sudden Death round is played.
+    fn round_label(&self) -> Option<String> {
+        match (self.round, self.max_rounds) {
+            (0, _) => None,
+            (round, 0) => Some(format!("Sudden Death {round}")),
+            (round, max) => Some(format!("Sudden Death {round}/{max}")),
+        }
+    }
+
     fn populate_instance_array(&self, array: &mut InstanceArray) {
         for card in self.playing_field.cards.iter().flatten() {
             card.add_to_instance_array(array);
@@ -427,18 +591,14 @@ impl Board {
     //     }
     // }
 
-    // fn draw_state_stack(&self, canvas: &mut Canvas) {
-    //     let text_raw = self
-    //         .state_stack
-    //         .iter()
-    //         .rev()
-    //         .map(|state| format!("{state:?}\n"))
-    //         .collect::<String>();
-
-    //     let text = Text::new(text_raw);
-    //     let text_pos = [300., 520.];
-    //     canvas.draw(&text, DrawParam::default().dest(text_pos));
-    // }
+    /// The board states, the active one first.
+    pub fn describe(&self) -> String {
+        self.state_stack
+            .iter()
+            .rev()
+            .map(|state| format!("{state:?}\n"))
+            .collect()
+    }
 
     // fn draw_ai_state_stack(&self, canvas: &mut Canvas) {
     //     let text_raw = self
@@ -455,7 +615,8 @@ impl Board {
     //     canvas.draw(&text, DrawParam::default().dest(text_pos));
     // }
 
-    fn calculate_score(&self) -> (usize, usize) {
+    /// The cards each side controls, red first.
+    pub fn calculate_score(&self) -> (usize, usize) {
         self.playing_field
             .cards
             .iter()
@@ -468,37 +629,6 @@ impl Board {
             })
     }
 
-    fn draw_score(&self, array: &mut InstanceArray) {
-        let (red_score, blue_score) = self.calculate_score();
-
-        for (score, pos) in [red_score, blue_score]
-            .iter()
-            .zip(&[consts::RED_SCORE_POS, consts::BLUE_SCORE_POS])
-        {
-            let digit_id = match score {
-                0 => consts::BIG_DIGIT_0_SPRITE_ID,
-                1 => consts::BIG_DIGIT_1_SPRITE_ID,
-                2 => consts::BIG_DIGIT_2_SPRITE_ID,
-                3 => consts::BIG_DIGIT_3_SPRITE_ID,
-                4 => consts::BIG_DIGIT_4_SPRITE_ID,
-                5 => consts::BIG_DIGIT_5_SPRITE_ID,
-                6 => consts::BIG_DIGIT_6_SPRITE_ID,
-                7 => consts::BIG_DIGIT_7_SPRITE_ID,
-                8 => consts::BIG_DIGIT_8_SPRITE_ID,
-                9 => consts::BIG_DIGIT_9_SPRITE_ID,
-                _ => panic!("Wrong score value {score}"),
-            };
-            let score_rect = self.sprite_sheet.create_sprite(digit_id).rect;
-
-            array.push(
-                DrawParam::default()
-                    .src(score_rect)
-                    .dest(*pos)
-                    .scale([2.0, 2.0]),
-            );
-        }
-    }
-
     pub fn turn_marker_status(&self) -> [bool; 2] {
         [
             matches!(self.state_stack.last().unwrap(), State::RedPlayerTurn(_)),
@@ -506,6 +636,16 @@ impl Board {
         ]
     }
 
+    /// Every card of the match, on the board or still in a hand.
+    pub fn match_cards(&self) -> impl Iterator<Item = &CardEntity> {
+        self.playing_field
+            .cards
+            .iter()
+            .chain(&self.red_hand.cards)
+            .chain(&self.blue_hand.cards)
+            .flatten()
+    }
+
     fn calculate_result(&mut self) -> (usize, usize) {
         let (red_score, blue_score) = self
             .playing_field
@@ -595,6 +735,7 @@ impl Board {
         //
 
         let card_suit = card.controller;
+        let own_effect = card.elemental_effect();
 
         let border_mask = utils::border_mask(cell_id);
         let card_ranks = card.rank_slice();
@@ -658,16 +799,18 @@ impl Board {
 
         // Same Rule
 
-        let cards_flipped_same = if self.rules.same {
+        // Walls count towards Same Wall but never flip.
+        let same_matches = if self.rules.same {
             if self.rules.same_wall {
-                let wall_tmp = utils::check_same(card_ranks, ranks_other_wall);
-                core::array::from_fn(|i| wall_tmp[i] && border_mask[i].is_some())
+                utils::check_same(card_ranks, ranks_other_wall)
             } else {
                 utils::check_same(card_ranks, ranks_other)
             }
         } else {
             [false; 4]
         };
+        let cards_flipped_same: [bool; 4] =
+            core::array::from_fn(|i| same_matches[i] && border_mask[i].is_some());
 
         // Plus check
         //
@@ -702,25 +845,57 @@ impl Board {
 
             cards_flipped[i] = Some(CardFlipped {
                 cell_id: border_mask[i].unwrap(),
-                combo: Combo::None,
+                combo: Capture::Normal,
             });
 
             if *same {
-                cards_flipped[i].as_mut().unwrap().combo = Combo::Same;
+                cards_flipped[i].as_mut().unwrap().combo = Capture::Same;
                 continue;
             }
             if plus {
-                cards_flipped[i].as_mut().unwrap().combo = Combo::Plus;
+                cards_flipped[i].as_mut().unwrap().combo = Capture::Plus;
                 continue;
             }
             if *normal {
                 cards_flipped[i] = Some(CardFlipped {
                     cell_id: border_mask[i].unwrap(),
-                    combo: Combo::None,
+                    combo: Capture::Normal,
                 });
             }
         }
 
+        let edges = (0..4)
+            .filter_map(|i| {
+                let wall = border_mask[i].is_none();
+                let other = if wall {
+                    same_matches[i].then_some(10)?
+                } else {
+                    ranks_other[i]?
+                };
+                let reason = if same_matches[i] {
+                    Some(Reason::Same)
+                } else if cards_flipped_plus[i] {
+                    Some(Reason::Plus)
+                } else if cards_flipped_normal[i] {
+                    Some(Reason::Normal)
+                } else {
+                    None
+                };
+                Some(Edge {
+                    side: i,
+                    own: card_ranks[i],
+                    own_effect,
+                    other,
+                    other_effect: ranks_other_elemental[i]
+                        .map_or(0, |r| i16::from(r) - i16::from(other)),
+                    wall,
+                    reason,
+                })
+            })
+            .collect();
+        self.capture_overlay
+            .add(self.playing_field.hitboxes[cell_id], edges);
+
         let card = self.playing_field.cards[cell_id]
             .as_mut()
             .expect("Wrong card id: {cell_id}!");
@@ -737,28 +912,180 @@ impl Board {
             .filter(|(i, _)| self.playing_field.cards[*i].is_none())
     }
 
+    /// Places `card` on the board, its move goes out as `CardPlaced`.
     pub fn put_card(&mut self, cell_id: usize, mut card: CardEntity) {
         let new_pos = self.playing_field.hitboxes[cell_id].point();
         card.reset_focus_tweens();
         card.mark_unchecked();
         card.start_move_tween(new_pos);
 
-        let card_elem = self.card_atlas.cards[card.id].element;
+        card.elemental_effect = self.elemental_effect(card.id, cell_id);
+        self.score.new_move();
+        self.capture_overlay.clear();
+        self.events.publish(GameEvent::CardPlaced {
+            suit: card.controller,
+            card: self.card_atlas.cards[card.id].key(),
+            cell: cell_id,
+            effect: card.elemental_effect,
+        });
+        self.playing_field.cards[cell_id] = Some(card);
+    }
+
+    fn elemental_effect(&self, card_id: usize, cell_id: usize) -> ElementalEffect {
+        let card_elem = self.card_atlas.cards[card_id].element;
         let cell_elem = self.playing_field.elem[cell_id].as_ref().map(|e| e.element);
         match (card_elem, cell_elem) {
-            (_, None) => card.elemental_effect = ElementalEffect::None,
-            (Some(card_elem), Some(cell_elem)) if card_elem == cell_elem => {
-                card.elemental_effect = ElementalEffect::Bonus;
+            (_, None) => ElementalEffect::None,
+            (Some(card_elem), Some(cell_elem)) if card_elem == cell_elem => ElementalEffect::Bonus,
+            (_, Some(_)) => ElementalEffect::Malus,
+        }
+    }
+
+    /// The board as it stands, meant to be taken at the start of a turn.
+    pub fn suspend(&self) -> SavedBoard {
+        let save = |card: &CardEntity| SavedCard {
+            card: self.card_atlas.cards[card.id].key(),
+            controller: card.controller,
+            hidden: card.flipped,
+        };
+        let turn = self
+            .state_stack
+            .iter()
+            .rev()
+            .find_map(|state| match state {
+                State::RedPlayerTurn(_) => Some(Suit::Red),
+                State::BluePlayerTurn(_) => Some(Suit::Blue),
+                _ => None,
+            })
+            .unwrap_or(Suit::Red);
+        SavedBoard {
+            rules: self.rules.clone(),
+            turn,
+            red_hand: self
+                .red_hand
+                .cards
+                .iter()
+                .map(|c| c.as_ref().map(save))
+                .collect(),
+            blue_hand: self
+                .blue_hand
+                .cards
+                .iter()
+                .map(|c| c.as_ref().map(save))
+                .collect(),
+            field: self
+                .playing_field
+                .cards
+                .iter()
+                .map(|c| c.as_ref().map(save))
+                .collect(),
+            elements: self
+                .playing_field
+                .elem
+                .iter()
+                .map(|e| e.as_ref().map(|e| e.element))
+                .collect(),
+            stats: self.stats.clone(),
+            round: self.round,
+            seed: self.seed,
+            difficulty: self.opponent.difficulty(),
+            think_delay: Some(self.opponent.think_delay()),
+        }
+    }
+
+    /// Puts every card where `saved` left it, at rest, and hands the turn to
+    /// the side about to play.
+    pub fn resume(&mut self, saved: &SavedBoard) -> Result<(), String> {
+        if saved.red_hand.len() > 5 || saved.blue_hand.len() > 5 {
+            return Err("a hand holds more than 5 cards".into());
+        }
+        if saved.field.len() != 9 || saved.elements.len() != 9 {
+            return Err("the board does not have 9 cells".into());
+        }
+        let entity = |saved: &SavedCard, pos: Point2<f32>| {
+            let id = self
+                .card_atlas
+                .position(&saved.card)
+                .ok_or_else(|| format!("unknown card `{}`", saved.card))?;
+            Ok::<_, String>(CardEntity::new(
+                id,
+                pos,
+                saved.controller,
+                saved.hidden,
+                &self.card_atlas,
+                &self.sprite_sheet,
+            ))
+        };
+
+        let mut red_hand = Hand::empty(Suit::Red, &self.card_atlas, &self.sprite_sheet);
+        let mut blue_hand = Hand::empty(Suit::Blue, &self.card_atlas, &self.sprite_sheet);
+        for (hand, cards) in [
+            (&mut red_hand, &saved.red_hand),
+            (&mut blue_hand, &saved.blue_hand),
+        ] {
+            for (n, card) in cards.iter().enumerate() {
+                if let Some(card) = card {
+                    let mut card = entity(card, [0.0, 0.0].into())?;
+                    // Saved before the Open rule turned them.
+                    card.flipped &= !saved.rules.open;
+                    hand.set_card_entity(n, card);
+                }
             }
-            (Some(card_elem), Some(cell_elem)) if card_elem != cell_elem => {
-                card.elemental_effect = ElementalEffect::Malus;
+        }
+        let mut field = Vec::new();
+        for (cell, card) in saved.field.iter().enumerate() {
+            if let Some(card) = card {
+                let pos = self.playing_field.hitboxes[cell].point();
+                field.push((cell, entity(card, pos)?));
             }
-            (None, Some(_)) => {
-                card.elemental_effect = ElementalEffect::Malus;
+        }
+
+        self.playing_field.clear();
+        for (cell, element) in saved.elements.iter().enumerate() {
+            if let Some(element) = element {
+                let pos = self.playing_field.hitboxes[cell].point();
+                self.playing_field.elem[cell] = Some(ElementEntity::new(*element, pos));
             }
-            (_, _) => unreachable!(),
         }
-        self.playing_field.cards[cell_id] = Some(card);
+        for (cell, mut card) in field {
+            card.elemental_effect = self.elemental_effect(card.id, cell);
+            card.reset_focus_tweens();
+            card.mark_checked();
+            self.playing_field.cards[cell] = Some(card);
+        }
+        self.activate_elem();
+        self.deactivate_occupied_elem();
+
+        self.red_hand = red_hand;
+        self.blue_hand = blue_hand;
+        self.rules = saved.rules.clone();
+        self.stats = saved.stats.clone();
+        self.score.reset();
+        self.capture_overlay.clear();
+        self.round = saved.round;
+        self.seed = saved.seed;
+        self.opponent.set_difficulty(saved.difficulty);
+        if let Some(think_delay) = saved.think_delay {
+            self.opponent.set_think_delay(think_delay);
+        }
+        self.opponent.reset();
+        self.events.clear();
+        self.state_stack = vec![
+            State::Finish,
+            match saved.turn {
+                Suit::Red => State::RedPlayerTurn(TurnPhase::Pick),
+                Suit::Blue => State::BluePlayerTurn(TurnPhase::Pick),
+            },
+        ];
+        Ok(())
+    }
+
+    /// A generator for the next random draw of the duel. Only its seed is
+    /// kept, so it can be saved with the board.
+    pub fn rng(&mut self) -> StdRng {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.seed = rng.gen();
+        rng
     }
 
     // pub fn card_to_array(&self) -> [&CardEntity; 10] {
@@ -816,13 +1143,14 @@ impl Board {
     //     self.final_message.outcome
     // }
 
-    pub fn update(&mut self, ctx: &mut Context) -> Option<Event> {
+    pub fn update(&mut self, ctx: &mut Context, events: &mut EventBus<Event>) {
         // if self.state_stack.is_empty() {
         //     return Some(Event::Quit);
         // }
 
-        let mouse_pos = ctx.mouse.position();
+        let mouse_pos = layout::mouse_position(ctx);
         let dt = ctx.time.delta().as_secs_f32();
+        let anim_dt = dt * self.animation_speed;
 
         let is_left_pressed = ctx
             .mouse
@@ -832,21 +1160,7 @@ impl Board {
             .mouse
             .button_just_pressed(ggez::event::MouseButton::Right);
 
-        for card in self.blue_hand.cards.iter_mut().flatten() {
-            card.update(dt);
-        }
-
-        for card in self.red_hand.cards.iter_mut().flatten() {
-            card.update(dt);
-        }
-
-        for card in self.playing_field.cards.iter_mut().flatten() {
-            card.update(dt);
-        }
-
-        for e in self.playing_field.elem.iter_mut().flatten() {
-            e.update(dt);
-        }
+        self.animate(dt);
         let mut card_hover = None;
         let mut card_id_hover = None;
 
@@ -892,7 +1206,7 @@ impl Board {
 
         self.tooltip.update(dt, card_hover, card_id_hover);
 
-        self.combo_message.update(dt);
+        self.combo_message.update(anim_dt);
 
         match self.state_stack.last().expect("State stack is empty!") {
             State::WaitingPick => {}
@@ -919,7 +1233,8 @@ impl Board {
                                 self.state_stack.pop();
                                 self.state_stack
                                     .push(State::BluePlayerTurn(TurnPhase::Place));
-                                return Some(Event::PlaySound(Sfx::Move));
+                                events.publish(Event::PlaySound(Sfx::Move));
+                                return;
                             }
                             break;
                         };
@@ -928,12 +1243,12 @@ impl Board {
                         self.blue_hand.reset_foucus();
                     }
                     if play_sound {
-                        return Some(Event::PlaySound(Sfx::Move));
+                        events.publish(Event::PlaySound(Sfx::Move));
                     }
                 }
                 TurnPhase::Place => {
                     if !(is_left_pressed || is_right_pressed) {
-                        return None;
+                        return;
                     }
                     if is_right_pressed {
                         self.blue_hand.clear_selected();
@@ -967,7 +1282,7 @@ impl Board {
                         self.state_stack.push(State::Check);
                         self.state_stack.push(State::WaitingMove);
 
-                        return Some(Event::PlaySound(Sfx::Move));
+                        return;
                     }
                 }
             },
@@ -993,22 +1308,22 @@ impl Board {
                             self.state_stack.push(State::NextTurn(Suit::Blue));
                             self.state_stack.push(State::Check);
                             self.state_stack.push(State::WaitingMove);
-
-                            return Some(Event::PlaySound(Sfx::Move));
                         }
                         AiEvent::Focus => {
-                            return Some(Event::PlaySound(Sfx::Move));
+                            events.publish(Event::PlaySound(Sfx::Move));
                         }
                     }
                 }
             }
             State::NextTurn(player) => match player {
                 Suit::Blue => {
+                    self.events.publish(GameEvent::TurnStarted(Suit::Blue));
                     self.state_stack.pop();
                     self.state_stack
                         .push(State::BluePlayerTurn(TurnPhase::Pick));
                 }
                 Suit::Red => {
+                    self.events.publish(GameEvent::TurnStarted(Suit::Red));
                     self.state_stack.pop();
                     self.state_stack.push(State::RedPlayerTurn(TurnPhase::Pick));
                 }
@@ -1018,23 +1333,28 @@ impl Board {
                 //     return None;
                 // }
                 let flipped_cards = self.check_cards();
-                let combo_same = flipped_cards.iter().any(|c| matches!(c.combo, Combo::Same));
-                let combo_plus = flipped_cards.iter().any(|c| matches!(c.combo, Combo::Plus));
+                self.score_step(&flipped_cards);
+                let combo_same = flipped_cards.iter().any(|c| c.combo == Capture::Same);
+                let combo_plus = flipped_cards.iter().any(|c| c.combo == Capture::Plus);
+                for card in &flipped_cards {
+                    self.publish_capture(card.cell_id, card.combo);
+                }
                 if combo_same {
-                    self.combo_message.start(Combo::Same);
+                    self.combo_message.start(Capture::Same);
                 } else if combo_plus {
-                    self.combo_message.start(Combo::Plus);
+                    self.combo_message.start(Capture::Plus);
                 }
                 if combo_plus || combo_same {
                     self.state_stack.push(State::ComboCheck);
+                    return;
                 }
 
                 if !flipped_cards.is_empty() {
-                    return Some(Event::PlaySound(Sfx::Flip));
+                    return;
                 }
 
                 if !self.flip_animation_finished() {
-                    return None;
+                    return;
                 }
 
                 self.state_stack.pop();
@@ -1045,15 +1365,19 @@ impl Board {
             }
             State::ComboCheck => {
                 if !self.flip_animation_finished() {
-                    return None;
+                    return;
                 }
 
                 self.state_stack.pop();
                 if !self.cards_to_check().is_empty() {
                     let flipped_cards = self.check_cards();
+                    self.score_step(&flipped_cards);
                     if !flipped_cards.is_empty() {
-                        self.combo_message.start(Combo::Combo);
-                        return Some(Event::PlaySound(Sfx::Flip));
+                        self.combo_message.start(Capture::Combo);
+                        self.events.publish(GameEvent::ComboStep);
+                        for card in &flipped_cards {
+                            self.publish_capture(card.cell_id, Capture::Combo);
+                        }
                     }
                 }
             }
@@ -1072,12 +1396,68 @@ impl Board {
                     (_, _) => unreachable!(),
                 };
                 self.tooltip.active = false;
-                return Some(Event::GameSummary(outcome, self.rules.sudden_death));
+                let sudden_death = self.rules.sudden_death
+                    && (self.max_rounds == 0 || self.round < self.max_rounds);
+                self.events.publish(GameEvent::MatchEnded {
+                    outcome,
+                    sudden_death,
+                });
+                events.publish(Event::GameSummary(outcome, sudden_death));
             }
         };
+    }
+    fn score_step(&mut self, flipped_cards: &[CardFlipped]) {
+        let captures: Vec<_> = flipped_cards
+            .iter()
+            .filter_map(|flipped| {
+                let side = self.captured_by(flipped.cell_id)?;
+                Some((self.playing_field.hitboxes[flipped.cell_id].point(), side))
+            })
+            .collect();
+        self.score.capture_step(&captures);
+    }
 
-        None
+    /// Moves the animations of the cards, elements and score on, without
+    /// playing.
+    pub fn animate(&mut self, dt: f32) {
+        let anim_dt = dt * self.animation_speed;
+        let (red_score, blue_score) = self.calculate_score();
+        self.score.update(anim_dt, [red_score, blue_score]);
+        self.capture_overlay.update(dt);
+        for card in self.blue_hand.cards.iter_mut().flatten() {
+            card.update(anim_dt);
+        }
+
+        for card in self.red_hand.cards.iter_mut().flatten() {
+            card.update(anim_dt);
+        }
+
+        for card in self.playing_field.cards.iter_mut().flatten() {
+            card.update(anim_dt);
+        }
+
+        for e in self.playing_field.elem.iter_mut().flatten() {
+            e.update(anim_dt);
+        }
     }
+
+    /// The side a card being captured goes to. It only changes sides
+    /// halfway through its flip.
+    fn captured_by(&self, cell: usize) -> Option<Suit> {
+        let card = self.playing_field.cards[cell].as_ref()?;
+        Some(match card.controller {
+            Suit::Red => Suit::Blue,
+            Suit::Blue => Suit::Red,
+        })
+    }
+
+    fn publish_capture(&mut self, cell: usize, by: Capture) {
+        if let Some(suit) = self.captured_by(cell) {
+            self.events
+                .publish(GameEvent::CardCaptured { cell, suit, by });
+        }
+    }
+
     fn start(&mut self) {
         if self.deal_animation_finished() {
             self.activate_elem();
@@ -1120,15 +1500,7 @@ impl Board {
     }
 
     pub fn toggle_rule(&mut self, r: SpecialRule) {
-        match r {
-            SpecialRule::Open => self.rules.open = !self.rules.open,
-            SpecialRule::Elemental => self.rules.elemental = !self.rules.elemental,
-            SpecialRule::Random => self.rules.random = !self.rules.random,
-            SpecialRule::Same => self.rules.same = !self.rules.same,
-            SpecialRule::Wall => self.rules.same_wall = !self.rules.same_wall,
-            SpecialRule::Plus => self.rules.plus = !self.rules.plus,
-            SpecialRule::SuddenDeath => self.rules.sudden_death = !self.rules.sudden_death,
-        }
+        self.rules.toggle(r);
     }
 
     // fn update_menu(&mut self, ctx: &mut Context) {
@@ -1211,14 +1583,59 @@ impl Board {
     // }
 
     fn populate_elem(&mut self) {
-        let first_elem_n = thread_rng().gen_range(0..9);
+        let mut rng = self.rng();
+        let first_elem_n = rng.gen_range(0..9);
         for i in 0..9 {
-            if !(thread_rng().gen::<f32>() < consts::ELEM_PROB || i == first_elem_n) {
+            if !(rng.gen::<f32>() < consts::ELEM_PROB || i == first_elem_n) {
                 continue;
             }
-            let element: Element = rand::random();
+            let element: Element = rng.gen();
             let pos = self.playing_field.hitboxes[i].point();
             self.playing_field.elem[i] = Some(ElementEntity::new(element, pos));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placed(suit: Suit) -> GameEvent {
+        GameEvent::CardPlaced {
+            suit,
+            card: "base:0".into(),
+            cell: 4,
+            effect: ElementalEffect::None,
+        }
+    }
+
+    fn captured(suit: Suit, by: Capture) -> GameEvent {
+        GameEvent::CardCaptured { cell: 1, suit, by }
+    }
+
+    #[test]
+    fn combos_count_once_per_move_or_step() {
+        let mut stats = MatchStats::default();
+        let events = [
+            placed(Suit::Blue),
+            captured(Suit::Blue, Capture::Same),
+            captured(Suit::Blue, Capture::Same),
+            GameEvent::ComboStep,
+            captured(Suit::Blue, Capture::Combo),
+            captured(Suit::Blue, Capture::Combo),
+            GameEvent::ComboStep,
+            captured(Suit::Blue, Capture::Combo),
+            placed(Suit::Red),
+            captured(Suit::Red, Capture::Plus),
+            GameEvent::ComboStep,
+            captured(Suit::Red, Capture::Combo),
+        ];
+        for event in &events {
+            stats.notify(event);
+        }
+        assert_eq!((stats.same, stats.plus, stats.combo), (1, 0, 2));
+        assert_eq!(stats.best_chain, 5);
+        let moves: Vec<_> = stats.moves.iter().map(|m| (m.suit, m.cell)).collect();
+        assert_eq!(moves, [(Suit::Blue, 4), (Suit::Red, 4)]);
+    }
+}
@@ -0,0 +1,198 @@
+use ggez::graphics::{Canvas, Color, DrawParam, InstanceArray, PxScale, Text, TextFragment};
+
+use mint::Point2;
+use std::rc::Rc;
+use tween::{Linear, Tweener};
+
+use crate::consts;
+use crate::core::Suit;
+use crate::graphics::sprite::Atlas as SpriteAtlas;
+
+/// Seconds between two ticks of a score digit.
+const TICK_INTERVAL: f32 = 0.15;
+const DIGIT_SCALE: f32 = 2.0;
+/// A digit grows to this scale when it ticks and shrinks back.
+const PULSE_SCALE: f32 = 2.6;
+const PULSE_DURATION: f32 = 0.25;
+/// Seconds a "+1" floats up over a captured card.
+const POPUP_DURATION: f32 = 0.9;
+const POPUP_RISE: f32 = 40.0;
+/// Seconds the chain stays up after its last step.
+const CHAIN_DURATION: f32 = 1.5;
+
+struct Popup {
+    pos: Point2<f32>,
+    side: Suit,
+    timer: f32,
+}
+
+/// The score digits, ticking towards the score as cards change hands, the
+/// "+1" over every captured card and the cards each step of a chain took.
+pub struct Score {
+    /// Red then blue, `None` until the first update of a round.
+    shown: Option<[usize; 2]>,
+    tick_timer: f32,
+    pulse: [Tweener<f32, f32, Linear>; 2],
+    scale: [f32; 2],
+    popups: Vec<Popup>,
+    /// Cards captured by each step of the last move.
+    chain: Vec<usize>,
+    chain_timer: f32,
+    sprite_sheet: Rc<SpriteAtlas>,
+}
+
+impl Score {
+    pub fn new(sprite_sheet: &Rc<SpriteAtlas>) -> Self {
+        let rest = || Tweener::new(DIGIT_SCALE, DIGIT_SCALE, 0.0, Linear);
+        Self {
+            shown: None,
+            tick_timer: 0.0,
+            pulse: [rest(), rest()],
+            scale: [DIGIT_SCALE; 2],
+            popups: Vec::new(),
+            chain: Vec::new(),
+            chain_timer: 0.0,
+            sprite_sheet: Rc::clone(sprite_sheet),
+        }
+    }
+
+    /// Shows the score as it is on the next update, for a new round.
+    pub fn reset(&mut self) {
+        self.shown = None;
+        self.scale = [DIGIT_SCALE; 2];
+        self.popups.clear();
+        self.chain.clear();
+    }
+
+    /// Starts the chain of a card just placed.
+    pub fn new_move(&mut self) {
+        self.chain.clear();
+    }
+
+    /// One step of a move: a "+1" over every captured card, `side` being the
+    /// side that took it.
+    pub fn capture_step(&mut self, captures: &[(Point2<f32>, Suit)]) {
+        if captures.is_empty() {
+            return;
+        }
+        for (pos, side) in captures {
+            self.popups.push(Popup {
+                pos: *pos,
+                side: *side,
+                timer: 0.0,
+            });
+        }
+        self.chain.push(captures.len());
+        self.chain_timer = 0.0;
+    }
+
+    /// Ticks each digit one point at a time towards `score`, red first.
+    pub fn update(&mut self, dt: f32, score: [usize; 2]) {
+        for (scale, pulse) in self.scale.iter_mut().zip(&mut self.pulse) {
+            *scale = pulse.move_by(dt);
+        }
+        for popup in &mut self.popups {
+            popup.timer += dt;
+        }
+        self.popups.retain(|popup| popup.timer < POPUP_DURATION);
+        self.chain_timer += dt;
+
+        let Some(shown) = &mut self.shown else {
+            self.shown = Some(score);
+            return;
+        };
+        self.tick_timer += dt;
+        if self.tick_timer < TICK_INTERVAL {
+            return;
+        }
+        self.tick_timer = 0.0;
+        for side in 0..2 {
+            if shown[side] == score[side] {
+                continue;
+            }
+            if shown[side] < score[side] {
+                shown[side] += 1;
+            } else {
+                shown[side] -= 1;
+            }
+            self.pulse[side] = Tweener::new(PULSE_SCALE, DIGIT_SCALE, PULSE_DURATION, Linear);
+        }
+    }
+
+    /// The chain of the last move, while it is up and took more than one
+    /// card.
+    pub fn chain_label(&self) -> Option<String> {
+        if self.chain_timer >= CHAIN_DURATION || self.chain.iter().sum::<usize>() < 2 {
+            return None;
+        }
+        let steps: Vec<String> = self.chain.iter().map(ToString::to_string).collect();
+        Some(format!("Chain {}", steps.join(" > ")))
+    }
+
+    pub fn draw_digits(&self, array: &mut InstanceArray) {
+        let Some(shown) = self.shown else {
+            return;
+        };
+        for ((score, pos), scale) in shown
+            .iter()
+            .zip(&[consts::RED_SCORE_POS, consts::BLUE_SCORE_POS])
+            .zip(self.scale)
+        {
+            let sprite = self.sprite_sheet.create_sprite(digit_sprite(*score));
+            // Grows around the centre of the digit at rest.
+            let center = [
+                sprite.width.mul_add(DIGIT_SCALE / 2.0, pos[0]),
+                sprite.height.mul_add(DIGIT_SCALE / 2.0, pos[1]),
+            ];
+            array.push(
+                DrawParam::default()
+                    .src(sprite.rect)
+                    .offset([0.5, 0.5])
+                    .dest(center)
+                    .scale([scale, scale]),
+            );
+        }
+    }
+
+    pub fn draw_popups(&self, canvas: &mut Canvas) {
+        for popup in &self.popups {
+            let progress = popup.timer / POPUP_DURATION;
+            let alpha = 1.0 - progress * progress;
+            let color = match popup.side {
+                Suit::Red => Color::new(1.0, 0.55, 0.55, alpha),
+                Suit::Blue => Color::new(0.55, 0.75, 1.0, alpha),
+            };
+            let pos = [
+                popup.pos.x + consts::CARD_SIZE[0] / 2.0 - 15.0,
+                POPUP_RISE.mul_add(-progress, popup.pos.y + consts::CARD_SIZE[1] / 2.0),
+            ];
+            let fragment = |color| TextFragment {
+                text: "+1".into(),
+                color: Some(color),
+                font: Some("pixel font".into()),
+                scale: Some(PxScale::from(consts::FONT_SIZE)),
+            };
+            canvas.draw(
+                &Text::new(fragment(Color::new(0.2, 0.2, 0.2, alpha))),
+                [pos[0] + 2.0, pos[1] + 2.0],
+            );
+            canvas.draw(&Text::new(fragment(color)), pos);
+        }
+    }
+}
+
+const fn digit_sprite(score: usize) -> usize {
+    match score {
+        0 => consts::BIG_DIGIT_0_SPRITE_ID,
+        1 => consts::BIG_DIGIT_1_SPRITE_ID,
+        2 => consts::BIG_DIGIT_2_SPRITE_ID,
+        3 => consts::BIG_DIGIT_3_SPRITE_ID,
+        4 => consts::BIG_DIGIT_4_SPRITE_ID,
+        5 => consts::BIG_DIGIT_5_SPRITE_ID,
+        6 => consts::BIG_DIGIT_6_SPRITE_ID,
+        7 => consts::BIG_DIGIT_7_SPRITE_ID,
+        8 => consts::BIG_DIGIT_8_SPRITE_ID,
+        9 => consts::BIG_DIGIT_9_SPRITE_ID,
+        _ => panic!("Wrong score value"),
+    }
+}
@@ -0,0 +1,211 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use std::rc::Rc;
+
+use super::menu::MenuItem;
+use crate::campaign::{Campaign, Progress};
+use crate::consts::FONT_SIZE;
+use crate::core::CardAtlas;
+use crate::deck::DECK_SIZE;
+use crate::events::EventBus;
+use crate::graphics::text::{draw_label, row};
+use crate::graphics::TextBox;
+use crate::layout;
+use crate::utils::{Event, Rule, Sfx};
+
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+
+const LIST_POS: [f32; 2] = [40.0, 60.0];
+const LIST_DIMENSIONS: [f32; 2] = [300.0, 420.0];
+const INFO_POS: [f32; 2] = [370.0, 60.0];
+const INFO_DIMENSIONS: [f32; 2] = [390.0, 420.0];
+const TITLE_POS: [f32; 2] = [40.0, 15.0];
+const DUEL_BUTTON_POS: [f32; 2] = [300.0, 520.0];
+const BACK_BUTTON_POS: [f32; 2] = [440.0, 520.0];
+
+const RULE_LABELS: [&str; 7] = [
+    "Open",
+    "Elemental",
+    "Random",
+    "Same",
+    "Wall",
+    "Plus",
+    "Sudden Death",
+];
+
+/// The ladder of campaign opponents. Beaten opponents are marked, locked
+/// ones are greyed out until the one above them is beaten.
+pub struct CampaignMap {
+    list_bg: TextBox,
+    info_bg: TextBox,
+    items: Vec<MenuItem>,
+    duel: MenuItem,
+    back: MenuItem,
+    selected: usize,
+    card_atlas: Rc<CardAtlas>,
+}
+
+impl CampaignMap {
+    pub fn new(ctx: &mut Context, campaign: &Campaign, card_atlas: &Rc<CardAtlas>) -> Self {
+        let items = (0..campaign.opponents.len())
+            .map(|n| MenuItem {
+                label: String::new(),
+                disabled: false,
+                rect: Rect::new(
+                    LIST_POS[0] + 15.0,
+                    LINE_HEIGHT.mul_add(row(n), LIST_POS[1] + 10.0),
+                    LIST_DIMENSIONS[0] - 30.0,
+                    FONT_SIZE,
+                ),
+                callback: None,
+            })
+            .collect();
+
+        Self {
+            list_bg: TextBox::new(ctx, LIST_POS, LIST_DIMENSIONS),
+            info_bg: TextBox::new(ctx, INFO_POS, INFO_DIMENSIONS),
+            items,
+            duel: MenuItem {
+                label: "Duel".into(),
+                disabled: false,
+                rect: Rect::new(DUEL_BUTTON_POS[0], DUEL_BUTTON_POS[1], 60.0, FONT_SIZE),
+                callback: None,
+            },
+            back: MenuItem {
+                label: "Back".into(),
+                disabled: false,
+                rect: Rect::new(BACK_BUTTON_POS[0], BACK_BUTTON_POS[1], 60.0, FONT_SIZE),
+                callback: Some(Event::Finished),
+            },
+            selected: 0,
+            card_atlas: Rc::clone(card_atlas),
+        }
+    }
+
+    /// Selects the furthest unlocked opponent.
+    pub fn init(&mut self, campaign: &Campaign, progress: &Progress) {
+        self.selected = (0..campaign.opponents.len())
+            .rev()
+            .find(|n| progress.is_unlocked(campaign, *n))
+            .unwrap_or(0);
+        self.update_labels(campaign, progress);
+    }
+
+    fn update_labels(&mut self, campaign: &Campaign, progress: &Progress) {
+        for (n, (item, opponent)) in self.items.iter_mut().zip(&campaign.opponents).enumerate() {
+            let unlocked = progress.is_unlocked(campaign, n);
+            let mark = if progress.is_beaten(opponent) {
+                "*"
+            } else {
+                " "
+            };
+            item.label = if unlocked {
+                format!("{mark}{}", opponent.name)
+            } else {
+                " ???".into()
+            };
+            item.disabled = !unlocked;
+        }
+        self.duel.disabled = campaign.opponents.is_empty();
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        campaign: &Campaign,
+        progress: &Progress,
+        events: &mut EventBus<Event>,
+    ) {
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            events.publish(Event::Finished);
+            return;
+        }
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Return) && !self.duel.disabled {
+            events.publish(Event::PlayCampaign(self.selected));
+            return;
+        }
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let mouse = layout::mouse_position(ctx);
+        if self.back.rect.contains(mouse) {
+            events.publish(Event::Finished);
+        } else if self.duel.rect.contains(mouse) && !self.duel.disabled {
+            events.publish(Event::PlayCampaign(self.selected));
+        } else if let Some(n) = self.items.iter().position(|item| item.rect.contains(mouse)) {
+            if progress.is_unlocked(campaign, n) {
+                self.selected = n;
+                events.publish(Event::PlaySound(Sfx::Select));
+            } else {
+                events.publish(Event::PlaySound(Sfx::Cancel));
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, campaign: &Campaign, progress: &Progress) {
+        draw_label(canvas, "Campaign", TITLE_POS);
+        self.list_bg.draw(canvas);
+        for (n, item) in self.items.iter().enumerate() {
+            item.draw(canvas);
+            if n == self.selected {
+                draw_label(canvas, ">", [item.rect.x - 12.0, item.rect.y]);
+            }
+        }
+
+        self.info_bg.draw(canvas);
+        let line = |n: f32| {
+            [
+                INFO_POS[0] + 15.0,
+                LINE_HEIGHT.mul_add(n, INFO_POS[1] + 10.0),
+            ]
+        };
+        let Some(opponent) = campaign.opponents.get(self.selected) else {
+            draw_label(canvas, "No opponents", line(0.0));
+            self.back.draw(canvas);
+            return;
+        };
+
+        let status = if progress.is_beaten(opponent) {
+            "Beaten"
+        } else {
+            "Not beaten yet"
+        };
+        let deck = match opponent.deck.holds.len() {
+            0 => "Random deck".into(),
+            DECK_SIZE => "Fixed deck".into(),
+            n => format!("Holds {n} cards"),
+        };
+        let reward = self
+            .card_atlas
+            .position(&opponent.reward)
+            .map_or("?", |id| self.card_atlas.cards[id].name.as_str());
+        draw_label(canvas, &opponent.name, line(0.0));
+        draw_label(canvas, status, line(1.0));
+        draw_label(
+            canvas,
+            &format!("Difficulty: {}", opponent.ai.difficulty),
+            line(2.0),
+        );
+        draw_label(canvas, &deck, line(3.0));
+        draw_label(canvas, &format!("Reward: {reward}"), line(4.0));
+        draw_label(canvas, "Rules:", line(5.0));
+        let mut rules: Vec<&str> = Rule::iterator()
+            .zip(RULE_LABELS)
+            .filter(|(rule, _)| opponent.rules.enabled(**rule))
+            .map(|(_, label)| label)
+            .collect();
+        if rules.is_empty() {
+            rules.push("None");
+        }
+        for (n, label) in rules.iter().enumerate() {
+            draw_label(canvas, &format!("  {label}"), line(6.0 + row(n)));
+        }
+
+        self.duel.draw(canvas);
+        self.back.draw(canvas);
+    }
+}
@@ -1,16 +1,18 @@
 use ggez::event::MouseButton;
-use ggez::graphics::{
-    Canvas, Color, DrawParam, Image, InstanceArray, PxScale, Rect, Text, TextFragment,
-};
+use ggez::graphics::{Canvas, Color, DrawParam, InstanceArray, PxScale, Rect, Text, TextFragment};
+use ggez::input::keyboard::KeyCode;
 use ggez::Context;
 
-use mint::Point2;
-
 use std::rc::Rc;
 
+use super::board::Hand;
+use super::menu::MenuItem;
 use crate::consts::{FONT_SIZE, RIGHT_ARROW_SPRITE_ID};
-use crate::core::{self, CardAtlas};
+use crate::core::{self, next_element, next_level, Card, CardAtlas, Element};
+use crate::deck::Decks;
+use crate::graphics::text::draw_label;
 use crate::graphics::{sprite::Atlas, CardEntity, TextBox};
+use crate::layout;
 
 const NAV_BTNS_Y: f32 = 292.0;
 const PREV_BTN_X: f32 = 68.0;
@@ -30,6 +32,43 @@ const CARD_SELECT_VIEW_POS: [f32; 2] = [130.0, 92.0];
 const CARD_SELECT_DIMENSIONS: [f32; 2] = [350.0, 400.0];
 const CARD_SELECT_TEXT_POS: [f32; 2] = [175.0, 105.0];
 const CARD_SELECT_LINE_GAP: f32 = 10.0;
+
+const FILTER_POS: [f32; 2] = [130.0, 10.0];
+const FILTER_DIMENSIONS: [f32; 2] = [350.0, 75.0];
+const SEARCH_POS: [f32; 2] = [145.0, 20.0];
+const FILTERS_Y: f32 = 50.0;
+const SEARCH_MAX_LEN: usize = 16;
+const MAX_RANK: u8 = 9;
+const LEVEL_FILTER: usize = 0;
+const ELEMENT_FILTER: usize = 1;
+const RANK_FILTER: usize = 2;
+
+const DECK_PICK_POS: [f32; 2] = [130.0, 510.0];
+const DECK_PICK_DIMENSIONS: [f32; 2] = [350.0, 35.0];
+const DECK_PREV: usize = 0;
+const DECK_USE: usize = 1;
+const DECK_NEXT: usize = 2;
+const START_BUTTON_X: f32 = 270.0;
+
+pub enum Pick {
+    Card(usize),
+    Deck(Vec<usize>),
+    /// Takes the card in this hand slot back.
+    Remove(usize),
+    /// Marks a hand slot to swap with the next one clicked, `None` clears
+    /// the mark.
+    Mark(Option<usize>),
+    Swap(usize, usize),
+    /// The hand is full and confirmed.
+    Start,
+    /// The mouse moved onto another card, nothing is picked.
+    Hover,
+    /// A filter changed, nothing is picked.
+    Filter,
+    /// A card was clicked but the hand is already full.
+    Full,
+}
+
 pub struct MenuCardItem {
     pub label: String,
     pub id: usize,
@@ -61,22 +100,16 @@ pub enum PageNavEvent {
 }
 
 pub struct PageNavButton {
-    pub sprite_id: usize,
-    pub scale: Point2<f32>,
     pub rect: Rect,
     pub active: bool,
     pub flipped: bool,
     pub callback: Box<dyn Fn() -> PageNavEvent>,
     sprite_sheet: Rc<Atlas>,
-    pub bg_rect: Image,
 }
 
 impl PageNavButton {
-    pub fn new_right(ctx: &mut Context, sprite_sheet: &Rc<Atlas>) -> Self {
-        let bg_rect = Image::from_solid(ctx, 1, Color::from_rgb(0, 255, 0));
+    pub fn new_right(sprite_sheet: &Rc<Atlas>) -> Self {
         Self {
-            sprite_id: crate::consts::RIGHT_ARROW_SPRITE_ID,
-            scale: [2.0, 2.0].into(),
             rect: Rect {
                 x: NEXT_BTN_X,
                 y: NAV_BTNS_Y,
@@ -86,13 +119,12 @@ impl PageNavButton {
             active: true,
             flipped: false,
             callback: Box::new(|| PageNavEvent::Next),
-            bg_rect,
             sprite_sheet: Rc::clone(sprite_sheet),
         }
     }
 
-    pub fn new_left(ctx: &mut Context, sprite_sheet: &Rc<Atlas>) -> Self {
-        let mut btn = Self::new_right(ctx, sprite_sheet);
+    pub fn new_left(sprite_sheet: &Rc<Atlas>) -> Self {
+        let mut btn = Self::new_right(sprite_sheet);
         btn.callback = Box::new(|| PageNavEvent::Prev);
         btn.flipped = true;
         btn.rect = Rect {
@@ -105,19 +137,20 @@ impl PageNavButton {
         btn
     }
 
+    /// Moves the button, by default it sits next to the card picker.
+    pub const fn at(mut self, x: f32, y: f32) -> Self {
+        self.rect.x = x;
+        self.rect.y = y;
+        self
+    }
+
     pub fn draw(&self, _ctx: &mut Context, canvas: &mut Canvas, array: &mut InstanceArray) {
         if !self.active {
             return;
         }
 
-        //let pos = self.rect.point();
         let dimensions = self.rect.size();
 
-        // canvas.draw(
-        //     &self.bg_rect,
-        //     DrawParam::default().dest(pos).scale(dimensions),
-        // );
-
         array.clear();
         let rect = self.sprite_sheet.create_sprite(RIGHT_ARROW_SPRITE_ID).rect;
         let flip = if self.flipped { -1.0 } else { 1.0 };
@@ -137,54 +170,130 @@ impl PageNavButton {
         canvas.draw(array, [0.0, 0.0]);
     }
 }
+/// The list of cards to pick from, narrowed down by typing part of a name
+/// and by the level, element and lowest rank filters above it.
 pub struct PickMenu {
     pub active: bool,
     page: usize,
     pages: usize,
-    count: u8,
     cards: Vec<(usize, String)>,
     next_btn: PageNavButton,
     prev_btn: PageNavButton,
     items: Vec<Option<MenuCardItem>>,
     bg_rect: TextBox,
+    filter_bg: TextBox,
+    filter_items: Vec<MenuItem>,
+    search: String,
+    levels: Vec<u8>,
+    level: Option<u8>,
+    element: Option<Option<Element>>,
+    /// Every side of a card must be at least this strong.
+    min_rank: u8,
+    filtered: Vec<usize>,
     card_atlas: Rc<CardAtlas>,
 }
 
 impl PickMenu {
     pub fn new(ctx: &mut Context, sprite_sheet: &Rc<Atlas>, card_atlas: &Rc<CardAtlas>) -> Self {
-        let next_btn = PageNavButton::new_right(ctx, sprite_sheet);
-        let prev_btn = PageNavButton::new_left(ctx, sprite_sheet);
+        let next_btn = PageNavButton::new_right(sprite_sheet);
+        let prev_btn = PageNavButton::new_left(sprite_sheet);
         let items = vec![
             None, None, None, None, None, None, None, None, None, None, None,
         ];
         let bg_rect = TextBox::new(ctx, CARD_SELECT_VIEW_POS, CARD_SELECT_DIMENSIONS);
+        let filter_items = [145.0, 245.0, 380.0]
+            .iter()
+            .map(|x| MenuItem {
+                label: String::new(),
+                disabled: false,
+                rect: Rect::new(*x, FILTERS_Y, 0.0, FONT_SIZE),
+                callback: None,
+            })
+            .collect();
         let mut res = Self {
             active: true,
             page: 0,
-            pages: 0,
-            count: 0,
+            pages: 1,
             cards: Vec::with_capacity(11),
             next_btn,
             prev_btn,
             items,
             bg_rect,
+            filter_bg: TextBox::new(ctx, FILTER_POS, FILTER_DIMENSIONS),
+            filter_items,
+            search: String::new(),
+            levels: card_atlas.levels(),
+            level: None,
+            element: None,
+            min_rank: 1,
+            filtered: Vec::new(),
             card_atlas: Rc::clone(card_atlas),
         };
 
-        res.init();
+        res.apply_filters();
         res
     }
 
+    pub fn text_input(&mut self, character: char) {
+        if character.is_control() || self.search.chars().count() >= SEARCH_MAX_LEN {
+            return;
+        }
+        self.search.push(character);
+        self.apply_filters();
+    }
+
+    /// Rebuilds the list from the filters and goes back to the first page.
+    fn apply_filters(&mut self) {
+        let search = self.search.to_lowercase();
+        let matches = |card: &Card| {
+            card.name.to_lowercase().contains(&search)
+                && self.level.map_or(true, |level| card.level == level)
+                && self.element.map_or(true, |element| card.element == element)
+                && card
+                    .rank_as_slice()
+                    .iter()
+                    .all(|rank| *rank >= self.min_rank)
+        };
+        self.filtered = self
+            .card_atlas
+            .cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| matches(card))
+            .map(|(id, _)| id)
+            .collect();
+        self.pages = self.filtered.len().div_ceil(CARDS_PER_PAGE).max(1);
+        self.page = 0;
+
+        let labels = [
+            self.level
+                .map_or_else(|| "Lv: All".into(), |level| format!("Lv: {level}")),
+            match self.element {
+                None => "El: All".into(),
+                Some(None) => "El: None".into(),
+                Some(Some(element)) => format!("El: {element:?}"),
+            },
+            format!("Rank: {}+", self.min_rank),
+        ];
+        for (item, label) in self.filter_items.iter_mut().zip(labels) {
+            item.rect.w = f32::from(u8::try_from(label.len()).expect("Value is too big")) * 12.0;
+            item.label = label;
+        }
+        self.init();
+    }
+
     pub fn init(&mut self) {
         self.cards.clear();
 
         for item in &mut self.items {
             *item = None;
         }
-        self.cards = (0..110)
+        self.cards = self
+            .filtered
+            .iter()
             .skip(self.page * CARDS_PER_PAGE)
             .take(CARDS_PER_PAGE)
-            .map(|id| (id, self.card_atlas.cards[id].name.clone()))
+            .map(|id| (*id, self.card_atlas.cards[*id].name.clone()))
             .collect();
 
         for (i, card) in self.cards.iter().enumerate() {
@@ -205,16 +314,41 @@ impl PickMenu {
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context) -> Option<(usize, u8)> {
+    /// Erases the last searched character, false if the search is empty.
+    pub fn erase(&mut self) -> bool {
+        if self.search.pop().is_none() {
+            return false;
+        }
+        self.apply_filters();
+        true
+    }
+
+    /// `full` turns down picks from the list while the hand has no free slot.
+    pub fn update(&mut self, ctx: &mut Context, full: bool) -> Option<Pick> {
         if !self.active {
             return None;
         }
-        self.pages = 10;
 
         if !ctx.mouse.button_just_pressed(MouseButton::Left) {
             return None;
         }
 
+        let mouse = layout::mouse_position(ctx);
+        if let Some(filter) = self
+            .filter_items
+            .iter()
+            .position(|item| item.rect.contains(mouse))
+        {
+            match filter {
+                LEVEL_FILTER => self.level = next_level(&self.levels, self.level),
+                ELEMENT_FILTER => self.element = next_element(self.element),
+                RANK_FILTER => self.min_rank = self.min_rank % MAX_RANK + 1,
+                _ => unreachable!(),
+            }
+            self.apply_filters();
+            return Some(Pick::Filter);
+        }
+
         self.next_btn.active = true;
         self.prev_btn.active = true;
         // if collection.len() < 11 {
@@ -223,16 +357,18 @@ impl PickMenu {
         // }
 
         for item in self.items.iter().flatten() {
-            if item.rect.contains(ctx.mouse.position()) {
-                self.count += 1;
-                return Some((item.id, self.count - 1));
+            if item.rect.contains(mouse) {
+                if full {
+                    return Some(Pick::Full);
+                }
+                return Some(Pick::Card(item.id));
             }
         }
 
-        if self.next_btn.rect.contains(ctx.mouse.position()) {
+        if self.next_btn.rect.contains(mouse) {
             self.next_page();
         }
-        if self.prev_btn.rect.contains(ctx.mouse.position()) {
+        if self.prev_btn.rect.contains(mouse) {
             self.prev_page();
         }
 
@@ -241,18 +377,13 @@ impl PickMenu {
 
     pub fn update_hover(&self, ctx: &mut Context) -> Option<usize> {
         for item in self.items.iter().flatten() {
-            if item.rect.contains(ctx.mouse.position()) {
+            if item.rect.contains(layout::mouse_position(ctx)) {
                 return Some(item.id);
             }
         }
         None
     }
 
-    // pub fn reset(&mut self) {
-    //     self.count = 0;
-    //     self.page = 0;
-    // }
-
     fn next_page(&mut self) {
         if self.page == self.pages - 1 {
             self.page = 0;
@@ -284,9 +415,18 @@ impl PickMenu {
         //         .dest(CARD_SELECT_VIEW_POS),
         // );
 
+        self.filter_bg.draw(canvas);
+        draw_label(canvas, &format!("Search: {}_", self.search), SEARCH_POS);
+        for item in &self.filter_items {
+            item.draw(canvas);
+        }
+
         for item in self.items.iter().flatten() {
             item.draw(canvas);
         }
+        if self.filtered.is_empty() {
+            draw_label(canvas, "No card matches", CARD_SELECT_TEXT_POS);
+        }
         self.next_btn.draw(ctx, canvas, array);
         self.prev_btn.draw(ctx, canvas, array);
     }
@@ -296,7 +436,15 @@ pub struct CardSelect {
     pub card_menu: PickMenu,
     card_preview: CardEntity,
     show_preview: bool,
-    pub active: bool,
+    decks: Vec<(String, Vec<usize>)>,
+    deck: usize,
+    deck_bg: TextBox,
+    deck_items: Vec<MenuItem>,
+    start_item: MenuItem,
+    /// Cards in the hand as of the last update.
+    picked: usize,
+    /// The hand slot marked to be swapped.
+    swap: Option<usize>,
 }
 
 impl CardSelect {
@@ -306,24 +454,87 @@ impl CardSelect {
             0,
             [500.0, 330.0].into(),
             core::Suit::Blue,
-            core::Suit::Blue,
             false,
             card_atlas,
             sprite_sheet,
         );
+        let deck_items = ["<", "", ">"]
+            .iter()
+            .zip([15.0, 45.0, 325.0])
+            .map(|(label, x)| MenuItem {
+                label: String::from(*label),
+                disabled: false,
+                rect: Rect::new(
+                    DECK_PICK_POS[0] + x,
+                    DECK_PICK_POS[1] + 5.0,
+                    if label.is_empty() { 270.0 } else { 20.0 },
+                    FONT_SIZE,
+                ),
+                callback: None,
+            })
+            .collect();
         Self {
             card_menu,
             card_preview,
             show_preview: false,
-            active: true,
+            decks: Vec::new(),
+            deck: 0,
+            deck_bg: TextBox::new(ctx, DECK_PICK_POS, DECK_PICK_DIMENSIONS),
+            deck_items,
+            start_item: MenuItem {
+                label: String::from("Start"),
+                disabled: false,
+                rect: Rect::new(START_BUTTON_X, DECK_PICK_POS[1] + 5.0, 60.0, FONT_SIZE),
+                callback: None,
+            },
+            picked: 0,
+            swap: None,
         }
     }
     pub fn init(&mut self) {
-        self.card_menu.count = 0;
+        self.picked = 0;
+        self.swap = None;
+    }
+
+    /// Offers the playable saved decks as a quick pick.
+    pub fn set_decks(&mut self, decks: &Decks) {
+        let card_atlas = &self.card_menu.card_atlas;
+        self.decks = decks
+            .playable(card_atlas)
+            .filter_map(|deck| Some((deck.name.clone(), deck.resolve(card_atlas)?)))
+            .collect();
+        self.select_deck(0);
     }
+
+    fn select_deck(&mut self, n: usize) {
+        self.deck = n;
+        self.deck_items[DECK_USE].label = self
+            .decks
+            .get(n)
+            .map_or_else(String::new, |(name, _)| format!("Deck: {name}"));
+    }
+
+    fn show_decks(&self) -> bool {
+        self.picked == 0 && !self.decks.is_empty()
+    }
+
+    const fn hand_full(&self) -> bool {
+        self.picked >= 5
+    }
+
     pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas, array: &mut InstanceArray) {
         self.card_menu.draw(ctx, array, canvas);
 
+        if self.show_decks() {
+            self.deck_bg.draw(canvas);
+            for item in &self.deck_items {
+                item.draw(canvas);
+            }
+        } else if self.hand_full() {
+            self.deck_bg.draw(canvas);
+            self.start_item.draw(canvas);
+        }
+
         if self.show_preview {
             array.clear();
             self.card_preview.add_to_instance_array(array);
@@ -331,13 +542,83 @@ impl CardSelect {
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context) -> Option<(usize, u8)> {
+    /// Besides picking from the list, a click on a card in `hand` marks it
+    /// and a click on a second one swaps them. Right-click or Backspace with
+    /// an empty search takes a card back.
+    pub fn update(&mut self, ctx: &mut Context, hand: &Hand) -> Option<Pick> {
+        self.picked = hand.card_count();
+        if self.swap.is_some_and(|n| n >= self.picked) {
+            self.swap = None;
+        }
+
+        let previous = self.show_preview.then_some(self.card_preview.id);
         self.show_preview = false;
-        if let Some(id) = self.card_menu.update_hover(ctx) {
+        let hovered = self.card_menu.update_hover(ctx);
+        if let Some(id) = hovered {
             self.show_preview = true;
             self.card_preview.id = id;
         }
+        let hover_changed = hovered.is_some() && hovered != previous;
+        let mouse = layout::mouse_position(ctx);
+
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Back)
+            && !self.card_menu.erase()
+            && self.picked > 0
+        {
+            self.swap = None;
+            return Some(Pick::Remove(self.picked - 1));
+        }
+
+        let slot = (0..self.picked)
+            .rev()
+            .find(|n| hand.card_rect(*n).contains(mouse));
+        if let Some(n) = slot {
+            if ctx.mouse.button_just_pressed(MouseButton::Right) {
+                self.swap = None;
+                return Some(Pick::Remove(n));
+            }
+            if ctx.mouse.button_just_pressed(MouseButton::Left) {
+                return Some(match self.swap.take() {
+                    Some(other) if other != n => Pick::Swap(other, n),
+                    Some(_) => Pick::Mark(None),
+                    None => {
+                        self.swap = Some(n);
+                        Pick::Mark(Some(n))
+                    }
+                });
+            }
+        }
+
+        if self.hand_full()
+            && (ctx.keyboard.is_key_just_pressed(KeyCode::Return)
+                || ctx.mouse.button_just_pressed(MouseButton::Left)
+                    && self.start_item.rect.contains(mouse))
+        {
+            self.swap = None;
+            return Some(Pick::Start);
+        }
+
+        if self.show_decks() && ctx.mouse.button_just_pressed(MouseButton::Left) {
+            let n = self.decks.len();
+            match self
+                .deck_items
+                .iter()
+                .position(|item| item.rect.contains(mouse))
+            {
+                Some(DECK_PREV) => self.select_deck((self.deck + n - 1) % n),
+                Some(DECK_NEXT) => self.select_deck((self.deck + 1) % n),
+                Some(DECK_USE) => return Some(Pick::Deck(self.decks[self.deck].1.clone())),
+                _ => {}
+            }
+        }
+
+        let full = self.hand_full();
+        self.card_menu
+            .update(ctx, full)
+            .or_else(|| hover_changed.then_some(Pick::Hover))
+    }
 
-        self.card_menu.update(ctx)
+    pub fn text_input(&mut self, character: char) {
+        self.card_menu.text_input(character);
     }
 }
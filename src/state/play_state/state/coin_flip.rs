@@ -1,17 +1,130 @@
-use rand::{thread_rng, Rng};
+use ggez::graphics::{Canvas, DrawParam, InstanceArray};
+use rand::Rng;
 
+use std::rc::Rc;
+
+use crate::consts;
 use crate::core::Suit;
+use crate::graphics::sprite::Atlas as SpriteAtlas;
+
+/// Seconds between the first jumps of the cursor, each jump waits a little
+/// longer than the one before.
+const FIRST_INTERVAL: f32 = 0.06;
+const SLOWDOWN: f32 = 1.08;
+/// Seconds the cursor blinks over the side that starts.
+const BLINK_DURATION: f32 = 0.9;
+const BLINK_INTERVAL: f32 = 0.15;
+const CURSOR_SIZE: f32 = 28.0;
+/// Centre of the cursor, in the gap between each hand and the board.
+const RED_CURSOR_POS: [f32; 2] = [
+    consts::LEFT_HAND_OFFSET[0] + consts::CARD_SIZE[0] + 15.0,
+    consts::TOP_MARGIN + (consts::HAND_STEP * 4.0 + consts::CARD_SIZE[1]) / 2.0,
+];
+const BLUE_CURSOR_POS: [f32; 2] = [
+    consts::RIGHT_HAND_OFFSET[0] - 15.0,
+    consts::TOP_MARGIN + (consts::HAND_STEP * 4.0 + consts::CARD_SIZE[1]) / 2.0,
+];
 
-pub struct CoinFlip{
-    
+/// Picks the side that plays first. As in FF8, a cursor jumps between the
+/// hands, slows down and blinks over the side that starts.
+pub struct CoinFlip {
+    first: Suit,
+    cursor: Suit,
+    /// Jumps left before the cursor stops over `first`.
+    jumps: u32,
+    interval: f32,
+    timer: f32,
+    sprite_sheet: Rc<SpriteAtlas>,
 }
 
-impl CoinFlip{
-    pub fn first() -> Suit{
-        if thread_rng().gen::<f32>() > 0.5{
+impl CoinFlip {
+    pub fn new(sprite_sheet: &Rc<SpriteAtlas>) -> Self {
+        Self {
+            first: Suit::Blue,
+            cursor: Suit::Blue,
+            jumps: 0,
+            interval: FIRST_INTERVAL,
+            timer: 0.0,
+            sprite_sheet: Rc::clone(sprite_sheet),
+        }
+    }
+
+    /// Lets `rng` pick the side, the cursor jumps until it lands there.
+    pub fn toss(&mut self, rng: &mut impl Rng) {
+        let first = if rng.gen::<f32>() > 0.5 {
             Suit::Red
-        }else{
+        } else {
             Suit::Blue
+        };
+        let jumps = rng.gen_range(10..16);
+        self.start(first, jumps);
+    }
+
+    /// Points the cursor at `first` right away.
+    pub fn point(&mut self, first: Suit) {
+        self.start(first, 0);
+    }
+
+    fn start(&mut self, first: Suit, jumps: u32) {
+        self.first = first;
+        self.cursor = if jumps % 2 == 0 { first } else { other(first) };
+        self.jumps = jumps;
+        self.interval = FIRST_INTERVAL;
+        self.timer = 0.0;
+    }
+
+    /// Moves the cursor, returns the side that starts once it stopped
+    /// blinking.
+    pub fn update(&mut self, dt: f32) -> Option<Suit> {
+        self.timer += dt;
+        if self.jumps > 0 {
+            if self.timer >= self.interval {
+                self.timer = 0.0;
+                self.interval *= SLOWDOWN;
+                self.jumps -= 1;
+                self.cursor = other(self.cursor);
+            }
+            return None;
         }
+        (self.timer >= BLINK_DURATION).then_some(self.first)
+    }
+
+    /// The side the cursor is over, `None` while it blinks off.
+    pub fn cursor(&self) -> Option<Suit> {
+        let blink_off = self.jumps == 0 && self.timer % (2.0 * BLINK_INTERVAL) >= BLINK_INTERVAL;
+        (!blink_off).then_some(self.cursor)
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, array: &mut InstanceArray) {
+        let Some(side) = self.cursor() else {
+            return;
+        };
+        let sprite = self
+            .sprite_sheet
+            .create_sprite(consts::RIGHT_ARROW_SPRITE_ID);
+        // The arrow points right, at the player's hand.
+        let (pos, flip) = match side {
+            Suit::Red => (RED_CURSOR_POS, -1.0),
+            Suit::Blue => (BLUE_CURSOR_POS, 1.0),
+        };
+        array.clear();
+        array.push(
+            DrawParam::default()
+                .src(sprite.rect)
+                .offset([0.5, 0.5])
+                .dest(pos)
+                .scale([
+                    CURSOR_SIZE / sprite.width * flip,
+                    CURSOR_SIZE / sprite.height,
+                ]),
+        );
+        canvas.draw(array, [0.0, 0.0]);
+    }
+}
+
+const fn other(side: Suit) -> Suit {
+    match side {
+        Suit::Red => Suit::Blue,
+        Suit::Blue => Suit::Red,
     }
 }
@@ -0,0 +1,446 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, DrawParam, InstanceArray, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use std::rc::Rc;
+
+use super::card_selector::{MenuCardItem, PageNavButton};
+use super::menu::MenuItem;
+use crate::consts::{CARD_SIZE, FONT_SIZE};
+use crate::core::{CardAtlas, Suit};
+use crate::deck::{Deck, Decks, DECK_NAME_MAX_LEN, DECK_SIZE};
+use crate::events::EventBus;
+use crate::graphics::text::{draw_label, row};
+use crate::graphics::{sprite::Atlas, CardEntity, TextBox};
+use crate::layout;
+use crate::utils::{Event, Sfx};
+
+const MAX_DECKS: usize = 10;
+const CARDS_PER_PAGE: usize = 10;
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+
+const DECK_LIST_POS: [f32; 2] = [20.0, 20.0];
+const DECK_LIST_DIMENSIONS: [f32; 2] = [200.0, 340.0];
+
+const COLLECTION_POS: [f32; 2] = [240.0, 20.0];
+const COLLECTION_DIMENSIONS: [f32; 2] = [330.0, 340.0];
+const NAV_BTNS_Y: f32 = 384.0;
+const PREV_BTN_X: f32 = 276.0;
+const NEXT_BTN_X: f32 = 556.0;
+const PAGE_LABEL_POS: [f32; 2] = [380.0, 370.0];
+
+const PREVIEW_POS: [f32; 2] = [620.0, 20.0];
+const NAME_POS: [f32; 2] = [590.0, 200.0];
+const BUTTONS_POS: [f32; 2] = [600.0, 270.0];
+const MESSAGE_POS: [f32; 2] = [590.0, 375.0];
+
+const SLOTS_POS: [f32; 2] = [20.0, 420.0];
+const SLOT_STEP: f32 = 140.0;
+const SLOT_RAISE: f32 = -20.0;
+
+const SAVE_BUTTON: usize = 0;
+const DELETE_BUTTON: usize = 1;
+const BACK_BUTTON: usize = 2;
+
+/// Assembles, names and saves the five-card decks offered by the card picker.
+///
+/// Left click a slot and then another one to swap them, right click a slot to
+/// take its card out of the deck.
+pub struct DeckBuilder {
+    deck_list_bg: TextBox,
+    collection_bg: TextBox,
+    deck_items: Vec<MenuItem>,
+    card_items: Vec<MenuCardItem>,
+    buttons: Vec<MenuItem>,
+    next_btn: PageNavButton,
+    prev_btn: PageNavButton,
+    page: usize,
+    pages: usize,
+    selected_deck: Option<usize>,
+    name: String,
+    cards: Vec<usize>,
+    slots: Vec<CardEntity>,
+    swap: Option<usize>,
+    preview: CardEntity,
+    show_preview: bool,
+    message: Option<&'static str>,
+    card_atlas: Rc<CardAtlas>,
+    sprite_sheet: Rc<Atlas>,
+}
+
+impl DeckBuilder {
+    pub fn new(ctx: &mut Context, card_atlas: &Rc<CardAtlas>, sprite_sheet: &Rc<Atlas>) -> Self {
+        let labels = ["Save", "Delete", "Back"];
+        let buttons = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| MenuItem {
+                label: String::from(*label),
+                disabled: false,
+                rect: Rect::new(
+                    BUTTONS_POS[0],
+                    LINE_HEIGHT.mul_add(row(i), BUTTONS_POS[1]),
+                    100.0,
+                    FONT_SIZE,
+                ),
+                callback: None,
+            })
+            .collect();
+
+        let mut res = Self {
+            deck_list_bg: TextBox::new(ctx, DECK_LIST_POS, DECK_LIST_DIMENSIONS),
+            collection_bg: TextBox::new(ctx, COLLECTION_POS, COLLECTION_DIMENSIONS),
+            deck_items: Vec::with_capacity(MAX_DECKS + 1),
+            card_items: Vec::with_capacity(CARDS_PER_PAGE),
+            buttons,
+            next_btn: PageNavButton::new_right(sprite_sheet).at(NEXT_BTN_X, NAV_BTNS_Y),
+            prev_btn: PageNavButton::new_left(sprite_sheet).at(PREV_BTN_X, NAV_BTNS_Y),
+            page: 0,
+            pages: card_atlas.cards.len().div_ceil(CARDS_PER_PAGE).max(1),
+            selected_deck: None,
+            name: String::new(),
+            cards: Vec::with_capacity(DECK_SIZE),
+            slots: Vec::with_capacity(DECK_SIZE),
+            swap: None,
+            preview: CardEntity::new(
+                0,
+                PREVIEW_POS.into(),
+                Suit::Blue,
+                false,
+                card_atlas,
+                sprite_sheet,
+            ),
+            show_preview: false,
+            message: None,
+            card_atlas: Rc::clone(card_atlas),
+            sprite_sheet: Rc::clone(sprite_sheet),
+        };
+        res.update_page();
+        res
+    }
+
+    pub fn init(&mut self, decks: &Decks) {
+        self.page = 0;
+        self.update_page();
+        self.new_deck();
+        self.update_deck_list(decks);
+    }
+
+    pub fn text_input(&mut self, character: char) {
+        if character.is_control() || self.name.chars().count() >= DECK_NAME_MAX_LEN {
+            return;
+        }
+        self.name.push(character);
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, decks: &mut Decks, events: &mut EventBus<Event>) {
+        let mouse = layout::mouse_position(ctx);
+
+        self.show_preview = false;
+        if let Some(item) = self
+            .card_items
+            .iter()
+            .find(|item| item.rect.contains(mouse))
+        {
+            self.show_preview = true;
+            self.preview.id = item.id;
+        }
+
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Back) {
+            self.name.pop();
+        }
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Return) {
+            events.publish(Event::PlaySound(self.save(decks)));
+            return;
+        }
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            events.publish(Event::Finished);
+            return;
+        }
+
+        if ctx.mouse.button_just_pressed(MouseButton::Right) {
+            if let Some(n) = self.slot_at(mouse) {
+                self.cards.remove(n);
+                self.swap = None;
+                self.update_slots();
+                events.publish(Event::PlaySound(Sfx::Cancel));
+                return;
+            }
+        }
+
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        if let Some(id) = self
+            .card_items
+            .iter()
+            .find(|item| item.rect.contains(mouse))
+            .map(|item| item.id)
+        {
+            if self.cards.len() >= DECK_SIZE {
+                self.message = Some("The deck is full");
+                events.publish(Event::PlaySound(Sfx::Cancel));
+                return;
+            }
+            self.cards.push(id);
+            self.message = None;
+            self.update_slots();
+            events.publish(Event::PlaySound(Sfx::Select));
+            return;
+        }
+
+        if let Some(n) = self.slot_at(mouse) {
+            match self.swap.take() {
+                Some(other) => self.cards.swap(n, other),
+                None => self.swap = Some(n),
+            }
+            self.update_slots();
+            events.publish(Event::PlaySound(Sfx::Move));
+            return;
+        }
+
+        if let Some(n) = self
+            .deck_items
+            .iter()
+            .position(|item| item.rect.contains(mouse))
+        {
+            match decks.decks.get(n) {
+                Some(deck) => self.load_deck(n, deck),
+                None => self.new_deck(),
+            }
+            self.update_deck_list(decks);
+            events.publish(Event::PlaySound(Sfx::Select));
+            return;
+        }
+
+        match self
+            .buttons
+            .iter()
+            .position(|item| item.rect.contains(mouse))
+        {
+            Some(SAVE_BUTTON) => events.publish(Event::PlaySound(self.save(decks))),
+            Some(DELETE_BUTTON) => events.publish(Event::PlaySound(self.delete(decks))),
+            Some(BACK_BUTTON) => events.publish(Event::Finished),
+            _ => {}
+        }
+
+        if self.next_btn.rect.contains(mouse) {
+            self.page = (self.page + 1) % self.pages;
+            self.update_page();
+        }
+        if self.prev_btn.rect.contains(mouse) {
+            self.page = (self.page + self.pages - 1) % self.pages;
+            self.update_page();
+        }
+    }
+
+    /// Saves the deck being edited and returns the sound that goes with it.
+    fn save(&mut self, decks: &mut Decks) -> Sfx {
+        // The picker only offers full decks.
+        if self.cards.len() < DECK_SIZE {
+            self.message = Some("Five cards needed");
+            return Sfx::Cancel;
+        }
+        if self.selected_deck.is_none() && decks.decks.len() >= MAX_DECKS {
+            self.message = Some("No room left");
+            return Sfx::Cancel;
+        }
+
+        let name = self.name.trim();
+        let deck = Deck {
+            name: if name.is_empty() {
+                format!(
+                    "Deck {}",
+                    self.selected_deck.unwrap_or(decks.decks.len()) + 1
+                )
+            } else {
+                name.into()
+            },
+            cards: self
+                .cards
+                .iter()
+                .map(|id| self.card_atlas.cards[*id].key())
+                .collect(),
+        };
+        self.name.clone_from(&deck.name);
+
+        match self.selected_deck {
+            Some(n) => decks.decks[n] = deck,
+            None => {
+                decks.decks.push(deck);
+                self.selected_deck = Some(decks.decks.len() - 1);
+            }
+        }
+        self.write(decks, "Saved");
+        self.update_deck_list(decks);
+        Sfx::Select
+    }
+
+    fn delete(&mut self, decks: &mut Decks) -> Sfx {
+        let Some(n) = self.selected_deck else {
+            return Sfx::Cancel;
+        };
+        decks.decks.remove(n);
+        self.new_deck();
+        self.write(decks, "Deleted");
+        self.update_deck_list(decks);
+        Sfx::Cancel
+    }
+
+    fn write(&mut self, decks: &Decks, message: &'static str) {
+        self.message = Some(message);
+        if let Err(e) = decks.save() {
+            eprintln!("{e}");
+            self.message = Some("Unable to save");
+        }
+    }
+
+    fn new_deck(&mut self) {
+        self.selected_deck = None;
+        self.name.clear();
+        self.cards.clear();
+        self.message = None;
+        self.swap = None;
+        self.update_slots();
+    }
+
+    fn load_deck(&mut self, n: usize, deck: &Deck) {
+        self.selected_deck = Some(n);
+        self.name.clone_from(&deck.name);
+        // Cards from a pack that is no longer installed are left out.
+        self.cards = deck
+            .cards
+            .iter()
+            .filter_map(|key| self.card_atlas.position(key))
+            .take(DECK_SIZE)
+            .collect();
+        self.message = (self.cards.len() < deck.cards.len()).then_some("Some cards are missing");
+        self.swap = None;
+        self.update_slots();
+    }
+
+    fn slot_at(&self, point: mint::Point2<f32>) -> Option<usize> {
+        self.slots.iter().position(|card| {
+            Rect::new(card.pos.x, card.pos.y, CARD_SIZE[0], CARD_SIZE[1]).contains(point)
+        })
+    }
+
+    fn update_slots(&mut self) {
+        self.slots = self
+            .cards
+            .iter()
+            .enumerate()
+            .map(|(n, id)| {
+                let raise = if self.swap == Some(n) {
+                    SLOT_RAISE
+                } else {
+                    0.0
+                };
+                CardEntity::new(
+                    *id,
+                    [
+                        SLOT_STEP.mul_add(row(n), SLOTS_POS[0]),
+                        SLOTS_POS[1] + raise,
+                    ]
+                    .into(),
+                    Suit::Blue,
+                    false,
+                    &self.card_atlas,
+                    &self.sprite_sheet,
+                )
+            })
+            .collect();
+    }
+
+    fn update_deck_list(&mut self, decks: &Decks) {
+        let new_deck = (decks.decks.len() < MAX_DECKS).then_some("New deck");
+        self.deck_items = decks
+            .decks
+            .iter()
+            .map(|deck| deck.name.as_str())
+            .chain(new_deck)
+            .enumerate()
+            .map(|(i, name)| MenuItem {
+                label: if self.selected_deck == Some(i) {
+                    format!("> {name}")
+                } else {
+                    name.into()
+                },
+                disabled: i == decks.decks.len(),
+                rect: Rect::new(
+                    DECK_LIST_POS[0] + 10.0,
+                    LINE_HEIGHT.mul_add(row(i), DECK_LIST_POS[1] + 10.0),
+                    DECK_LIST_DIMENSIONS[0] - 20.0,
+                    FONT_SIZE,
+                ),
+                callback: None,
+            })
+            .collect();
+    }
+
+    fn update_page(&mut self) {
+        self.card_items = self
+            .card_atlas
+            .cards
+            .iter()
+            .enumerate()
+            .skip(self.page * CARDS_PER_PAGE)
+            .take(CARDS_PER_PAGE)
+            .enumerate()
+            .map(|(i, (id, card))| MenuCardItem {
+                label: card.name.clone(),
+                id,
+                rect: Rect::new(
+                    COLLECTION_POS[0] + 15.0,
+                    LINE_HEIGHT.mul_add(row(i), COLLECTION_POS[1] + 10.0),
+                    COLLECTION_DIMENSIONS[0] - 30.0,
+                    FONT_SIZE,
+                ),
+            })
+            .collect();
+    }
+
+    pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas, array: &mut InstanceArray) {
+        self.deck_list_bg.draw(canvas);
+        self.collection_bg.draw(canvas);
+
+        for item in &self.deck_items {
+            item.draw(canvas);
+        }
+        for item in &self.card_items {
+            item.draw(canvas);
+        }
+        for item in &self.buttons {
+            item.draw(canvas);
+        }
+
+        draw_label(
+            canvas,
+            &format!("{}/{}", self.page + 1, self.pages),
+            PAGE_LABEL_POS,
+        );
+        draw_label(canvas, "Name:", NAME_POS);
+        draw_label(
+            canvas,
+            &format!("{}_", self.name),
+            [NAME_POS[0], NAME_POS[1] + LINE_HEIGHT],
+        );
+        if let Some(message) = self.message {
+            draw_label(canvas, message, MESSAGE_POS);
+        }
+
+        self.next_btn.draw(ctx, canvas, array);
+        self.prev_btn.draw(ctx, canvas, array);
+
+        array.clear();
+        for card in &self.slots {
+            card.add_to_instance_array(array);
+        }
+        if self.show_preview {
+            self.preview.add_to_instance_array(array);
+        }
+        canvas.draw(array, DrawParam::default());
+    }
+}
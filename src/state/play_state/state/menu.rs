@@ -1,4 +1,5 @@
 use crate::core::Rules;
+use crate::events::EventBus;
 use crate::utils::{Event, Rule};
 use ggez::event::MouseButton;
 use ggez::graphics::{Canvas, Color, InstanceArray, PxScale, Rect, Text, TextFragment};
@@ -6,17 +7,18 @@ use ggez::Context;
 
 use crate::consts;
 use crate::graphics::TextBox;
+use crate::layout;
 
 const MENU_BG_POS: [f32; 2] = [286.0, 116.0];
 const MENU_BG_DIMENSIONS: [f32; 2] = [240.0, 312.0];
-const PLAY_BUTTON_POS: [f32; 2] = [370.0, 336.0];
-const QUIT_BUTTON_POS: [f32; 2] = [370.0, 377.0];
+const MENU_BUTTON_X: f32 = 370.0;
+const LEVELS_PER_ROW: usize = 10;
 
 pub struct MenuItem {
     pub label: String,
     pub disabled: bool,
     pub rect: Rect,
-    pub callback: Event,
+    pub callback: Option<Event>,
 }
 impl MenuItem {
     pub fn draw(&self, canvas: &mut Canvas) {
@@ -47,17 +49,18 @@ impl MenuItem {
 pub struct Menu {
     bg_rect: TextBox,
     items: Vec<MenuItem>,
+    opponent_item: MenuItem,
 }
 
 impl Menu {
-    pub fn new(ctx: &mut Context) -> Self {
+    pub fn new(ctx: &mut Context, levels: &[u8]) -> Self {
         let mut items: Vec<MenuItem> = Vec::with_capacity(10);
 
         let rules_label = MenuItem {
             label: "Rules:".into(),
             disabled: false,
             rect: Rect::new(MENU_BG_POS[0] + 10.0, 15.0, 0.0, 0.0),
-            callback: Event::None,
+            callback: None,
         };
         let labels = [
             "Open",
@@ -82,7 +85,7 @@ impl Menu {
                     w: f32::from(u16::try_from(label.len()).expect("Value is too big")) * 10.0,
                     h: consts::FONT_SIZE,
                 },
-                callback: Event::ChangeRule(*rule),
+                callback: Some(Event::ChangeRule(*rule)),
             };
             items.push(item);
         }
@@ -97,13 +100,27 @@ impl Menu {
                 f32::from(u16::try_from("Difficulty:x".len()).expect("Value is too big")) * 10.0,
                 consts::FONT_SIZE,
             ),
-            callback: Event::ChangeDifficulty,
+            callback: Some(Event::ChangeDifficulty),
         };
 
         items.push(difficulty_label);
 
         items_size = f32::from(u8::try_from(items.len() - 1).expect("vec is too big"));
 
+        // Kept apart from `items` so the indices of the rows below stay put.
+        let mut opponent_item = MenuItem {
+            label: "Vs:".into(),
+            disabled: false,
+            rect: Rect::new(
+                MENU_BG_POS[0] + 10.0,
+                items_size.mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
+                MENU_BG_DIMENSIONS[0] - 20.0,
+                consts::FONT_SIZE,
+            ),
+            callback: Some(Event::ChangeOpponent),
+        };
+        items_size += 1.0;
+
         let cards_label = MenuItem {
             label: "Cards aviable:".into(),
             disabled: false,
@@ -113,70 +130,71 @@ impl Menu {
                 60.0,
                 consts::FONT_SIZE,
             ),
-            callback: Event::None,
+            callback: None,
         };
 
         items.push(cards_label);
 
-        items_size = f32::from(u8::try_from(items.len() - 1).expect("vec is too big"));
+        items_size += 1.0;
 
-        for i in 1..11 {
-            let i_small = u8::try_from(i - 1).expect("Value is too big!");
+        for (i, level) in levels.iter().enumerate() {
+            let column = u8::try_from(i % LEVELS_PER_ROW).expect("Value is too big!");
+            let row = u8::try_from(i / LEVELS_PER_ROW).expect("Value is too big!");
             let item = MenuItem {
-                label: format!("{i}"),
+                label: format!("{level}"),
                 disabled: false,
                 rect: Rect::new(
-                    20.0f32.mul_add(f32::from(i_small), MENU_BG_POS[0] + 25.0),
-                    items_size.mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
+                    20.0f32.mul_add(f32::from(column), MENU_BG_POS[0] + 25.0),
+                    (items_size + f32::from(row))
+                        .mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
                     20.0,
                     consts::FONT_SIZE,
                 ),
-                callback: Event::ToggleCards(i - 1),
+                callback: Some(Event::ToggleCards(i)),
             };
 
             items.push(item);
         }
 
-        items_size += 1.0;
+        let level_rows = levels.len().div_ceil(LEVELS_PER_ROW).max(1);
+        items_size += f32::from(u8::try_from(level_rows).expect("Value is too big!"));
 
-        let play_button = MenuItem {
-            label: "Play".into(),
-            disabled: false,
-            rect: Rect::new(
-                PLAY_BUTTON_POS[0],
-                items_size.mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
-                60.0,
-                consts::FONT_SIZE,
-            ),
-            callback: Event::Play,
-        };
-
-        let quit_button = MenuItem {
-            label: "Quit".into(),
-            disabled: false,
-            rect: Rect::new(
-                QUIT_BUTTON_POS[0],
-                (items_size + 1.0).mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
-                60.0,
-                consts::FONT_SIZE,
-            ),
-            callback: Event::Quit,
-        };
+        let buttons = [
+            ("Play", 60.0, Event::Play),
+            ("Campaign", 100.0, Event::OpenCampaign),
+            ("Decks", 60.0, Event::EditDecks),
+            ("Album", 60.0, Event::OpenAlbum),
+            ("Stats", 60.0, Event::OpenStats),
+            ("Settings", 100.0, Event::EditSettings),
+            ("Quit", 60.0, Event::Quit),
+        ];
+        let button_rows = f32::from(u8::try_from(buttons.len()).expect("Value is too big!"));
+        for (row, (label, width, callback)) in (0u8..).zip(buttons) {
+            items.push(MenuItem {
+                label: label.into(),
+                disabled: false,
+                rect: Rect::new(
+                    MENU_BUTTON_X,
+                    (items_size + f32::from(row))
+                        .mul_add(consts::FONT_SIZE + 5.0, 20.0 + consts::FONT_SIZE),
+                    width,
+                    consts::FONT_SIZE,
+                ),
+                callback: Some(callback),
+            });
+        }
 
-        items_size += 2.0;
+        items_size += button_rows;
 
         let box_height = items_size.mul_add(consts::FONT_SIZE + 5.0, 50.0);
 
         let box_x_pos = (consts::WINDOW_DIMENSIONS[0] - MENU_BG_DIMENSIONS[0]) / 2.0;
         let box_y_pos = (consts::WINDOW_DIMENSIONS[1] - box_height) / 2.0;
 
-        //let items = vec![play_button, quit_button];
-        items.push(play_button);
-        items.push(quit_button);
-
         for item in &mut items {
             item.rect.y += box_y_pos;
         }
+        opponent_item.rect.y += box_y_pos;
 
         Self {
             bg_rect: TextBox::new(
@@ -185,6 +203,7 @@ impl Menu {
                 [MENU_BG_DIMENSIONS[0], box_height],
             ),
             items,
+            opponent_item,
         }
     }
     pub fn init(&mut self, rules: &Rules) {
@@ -194,6 +213,10 @@ impl Menu {
         self.items[8].label = format!("Difficulty: {n}");
     }
 
+    fn update_opponent(&mut self, opponent: &str) {
+        self.opponent_item.label = format!("Vs: {opponent}");
+    }
+
     fn update_rules(&mut self, rules: &Rules) {
         self.items[1].disabled = !rules.open;
         self.items[2].disabled = !rules.elemental;
@@ -203,7 +226,7 @@ impl Menu {
         self.items[6].disabled = !rules.plus;
         self.items[7].disabled = !rules.sudden_death;
     }
-    fn update_cards_aviable(&mut self, cards_aviable: &[bool; 10]) {
+    fn update_cards_aviable(&mut self, cards_aviable: &[bool]) {
         for (i, val) in cards_aviable.iter().enumerate() {
             self.items[i + 10].disabled = !val;
         }
@@ -213,22 +236,27 @@ impl Menu {
         &mut self,
         difficulty: usize,
         rules: &Rules,
-        cards_aviable: &[bool; 10],
+        cards_aviable: &[bool],
+        opponent: &str,
         ctx: &mut Context,
-    ) -> Option<Event> {
+        events: &mut EventBus<Event>,
+    ) {
         //let _rect = Rect::new(consts::BOARD_OFFSET[0], consts::BOARD_OFFSET[1], 240., 300.);
         self.update_rules(rules);
         self.update_difficulty(difficulty);
         self.update_cards_aviable(cards_aviable);
-        if ctx.mouse.button_just_pressed(MouseButton::Left) {
-            for item in &self.items {
-                if item.rect.contains(ctx.mouse.position()) {
-                    return Some(item.callback);
-                }
-            }
+        self.update_opponent(opponent);
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+        let mouse = layout::mouse_position(ctx);
+        if let Some(callback) = std::iter::once(&self.opponent_item)
+            .chain(&self.items)
+            .find(|item| item.rect.contains(mouse))
+            .and_then(|item| item.callback)
+        {
+            events.publish(callback);
         }
-
-        None
     }
 
     pub fn draw(&self, _ctx: &mut Context, _array: &mut InstanceArray, canvas: &mut Canvas) {
@@ -242,5 +270,6 @@ impl Menu {
         for item in &self.items {
             item.draw(canvas);
         }
+        self.opponent_item.draw(canvas);
     }
 }
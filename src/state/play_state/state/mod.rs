@@ -1,12 +1,28 @@
+mod album;
+mod campaign_map;
 mod coin_flip;
 mod menu;
+mod prompt;
 mod card_selector;
+mod deck_builder;
 mod board;
 mod banner;
+mod settings;
+mod stats;
+mod toast;
 
+pub use album::Album;
 pub use banner::Banner;
+pub use campaign_map::CampaignMap;
 pub use board::Board;
 pub use board::Hand;
+pub use board::MatchStats;
 pub use menu::Menu;
+pub use prompt::Prompt;
 pub use card_selector::CardSelect as CardPicker;
+pub use card_selector::Pick;
+pub use deck_builder::DeckBuilder;
+pub use settings::Settings;
+pub use stats::Stats;
+pub use toast::Toast;
 pub use coin_flip::CoinFlip;
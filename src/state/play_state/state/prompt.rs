@@ -0,0 +1,88 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use super::menu::MenuItem;
+use crate::consts::{self, FONT_SIZE};
+use crate::events::EventBus;
+use crate::graphics::text::{draw_label, row};
+use crate::graphics::TextBox;
+use crate::layout;
+use crate::utils::Event;
+
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+const WIDTH: f32 = 240.0;
+
+/// A box of choices over the screen, Escape picks `Event::Finished`. The
+/// pause menu of a duel is one.
+pub struct Prompt {
+    title: String,
+    pos: [f32; 2],
+    bg_rect: TextBox,
+    items: Vec<MenuItem>,
+}
+
+impl Prompt {
+    pub fn new(ctx: &mut Context, title: &str, choices: &[(&str, Event)]) -> Self {
+        let dimensions = [WIDTH, LINE_HEIGHT.mul_add(row(choices.len() + 1), 40.0)];
+        let pos = [
+            (consts::WINDOW_DIMENSIONS[0] - dimensions[0]) / 2.0,
+            (consts::WINDOW_DIMENSIONS[1] - dimensions[1]) / 2.0,
+        ];
+        let items = choices
+            .iter()
+            .enumerate()
+            .map(|(n, (label, callback))| MenuItem {
+                label: (*label).into(),
+                disabled: false,
+                rect: Rect::new(
+                    pos[0] + 30.0,
+                    LINE_HEIGHT.mul_add(row(n + 1), pos[1] + 15.0),
+                    WIDTH - 60.0,
+                    FONT_SIZE,
+                ),
+                callback: Some(*callback),
+            })
+            .collect();
+
+        Self {
+            title: title.into(),
+            pos,
+            bg_rect: TextBox::new(ctx, pos, dimensions),
+            items,
+        }
+    }
+
+    pub fn update(&self, ctx: &mut Context, events: &mut EventBus<Event>) {
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            events.publish(Event::Finished);
+            return;
+        }
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let mouse = layout::mouse_position(ctx);
+        if let Some(callback) = self
+            .items
+            .iter()
+            .find(|item| item.rect.contains(mouse))
+            .and_then(|item| item.callback)
+        {
+            events.publish(callback);
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        self.bg_rect.draw(canvas);
+        draw_label(
+            canvas,
+            &self.title,
+            [self.pos[0] + 15.0, self.pos[1] + 15.0],
+        );
+        for item in &self.items {
+            item.draw(canvas);
+        }
+    }
+}
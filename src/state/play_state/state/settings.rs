@@ -0,0 +1,337 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use super::menu::MenuItem;
+use crate::config::{
+    Config, FirstTurn, AI_DELAY_RANGE, ANIMATION_SPEED_RANGE, FADE_DURATION_RANGE, INTENSITY_RANGE,
+    MAX_DIFFICULTY, MAX_SUDDEN_DEATH_ROUNDS, TOOLTIP_DELAY_RANGE, VOLUME_RANGE,
+};
+use crate::consts::FONT_SIZE;
+use crate::events::EventBus;
+use crate::graphics::post::Effect;
+use crate::graphics::text::row;
+use crate::graphics::TextBox;
+use crate::layout;
+use crate::utils::{Event, Rule};
+
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+
+const SETTINGS_BG_POS: [f32; 2] = [170.0, 10.0];
+const SETTINGS_BG_DIMENSIONS: [f32; 2] = [460.0, 585.0];
+const LABEL_X: f32 = 185.0;
+const DECREASE_X: f32 = 470.0;
+const VALUE_X: f32 = 500.0;
+const INCREASE_X: f32 = 590.0;
+const ROWS_Y: f32 = 20.0;
+const RULES_X: f32 = 200.0;
+const RULES_PER_ROW: usize = 4;
+const RULE_GAP: f32 = 20.0;
+const BACK_BUTTON_POS: [f32; 2] = [370.0, 560.0];
+
+#[derive(Debug, Clone, Copy)]
+enum Setting {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    MuteMusic,
+    MuteSfx,
+    Effects,
+    EffectIntensity,
+    AnimationSpeed,
+    AiDelay,
+    TooltipDelay,
+    FadeDuration,
+    Difficulty,
+    SuddenDeathRounds,
+    FirstTurn,
+    ExplainCaptures,
+}
+
+const SETTINGS: [(Setting, &str); 15] = [
+    (Setting::MasterVolume, "Master volume"),
+    (Setting::MusicVolume, "Music volume"),
+    (Setting::SfxVolume, "Sound effects"),
+    (Setting::MuteMusic, "Mute music"),
+    (Setting::MuteSfx, "Mute effects"),
+    (Setting::Effects, "Screen effect"),
+    (Setting::EffectIntensity, "Effect intensity"),
+    (Setting::AnimationSpeed, "Animation speed"),
+    (Setting::AiDelay, "AI think delay"),
+    (Setting::TooltipDelay, "Tooltip delay"),
+    (Setting::FadeDuration, "Fade duration"),
+    (Setting::Difficulty, "Difficulty"),
+    (Setting::SuddenDeathRounds, "Sudden Death rounds"),
+    (Setting::FirstTurn, "First turn"),
+    (Setting::ExplainCaptures, "Explain captures"),
+];
+
+const RULE_LABELS: [&str; 7] = [
+    "Open",
+    "Elemental",
+    "Random",
+    "Same",
+    "Wall",
+    "Plus",
+    "Sudden Death",
+];
+
+/// Edits the config, every change is applied right away and the file is
+/// written when the screen is left.
+pub struct Settings {
+    bg_rect: TextBox,
+    labels: Vec<MenuItem>,
+    values: Vec<MenuItem>,
+    decrease: Vec<MenuItem>,
+    increase: Vec<MenuItem>,
+    rules: Vec<MenuItem>,
+    back: MenuItem,
+}
+
+impl Settings {
+    pub fn new(ctx: &mut Context) -> Self {
+        let item = |label: &str, x: f32, y: f32, w: f32| MenuItem {
+            label: label.into(),
+            disabled: false,
+            rect: Rect::new(x, y, w, FONT_SIZE),
+            callback: None,
+        };
+        let row_y = |i: usize| LINE_HEIGHT.mul_add(row(i), ROWS_Y);
+
+        let labels = SETTINGS
+            .iter()
+            .enumerate()
+            .map(|(i, (_, label))| item(label, LABEL_X, row_y(i), 0.0))
+            .collect();
+        let values = (0..SETTINGS.len())
+            .map(|i| item("", VALUE_X, row_y(i), 0.0))
+            .collect();
+        let decrease = (0..SETTINGS.len())
+            .map(|i| item("<", DECREASE_X, row_y(i), 20.0))
+            .collect();
+        let increase = (0..SETTINGS.len())
+            .map(|i| item(">", INCREASE_X, row_y(i), 20.0))
+            .collect();
+
+        let mut rules = vec![item("Default rules:", LABEL_X, row_y(SETTINGS.len()), 0.0)];
+        let mut x = RULES_X;
+        for (i, label) in RULE_LABELS.iter().enumerate() {
+            if i % RULES_PER_ROW == 0 {
+                x = RULES_X;
+            }
+            let line = SETTINGS.len() + 1 + i / RULES_PER_ROW;
+            let width = f32::from(u8::try_from(label.len()).expect("Value is too big")) * 15.0;
+            rules.push(item(label, x, row_y(line), width));
+            x += width + RULE_GAP;
+        }
+
+        Self {
+            bg_rect: TextBox::new(ctx, SETTINGS_BG_POS, SETTINGS_BG_DIMENSIONS),
+            labels,
+            values,
+            decrease,
+            increase,
+            rules,
+            back: item("Back", BACK_BUTTON_POS[0], BACK_BUTTON_POS[1], 60.0),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, config: &mut Config, events: &mut EventBus<Event>) {
+        self.update_values(config);
+
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            Self::close(config, events);
+            return;
+        }
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let mouse = layout::mouse_position(ctx);
+        if self.back.rect.contains(mouse) {
+            Self::close(config, events);
+            return;
+        }
+
+        let clicked = |items: &[MenuItem]| items.iter().position(|item| item.rect.contains(mouse));
+        if let Some(i) = clicked(&self.decrease) {
+            adjust(config, SETTINGS[i].0, -1);
+            events.publish(Event::SettingsChanged);
+        } else if let Some(i) = clicked(&self.increase) {
+            adjust(config, SETTINGS[i].0, 1);
+            events.publish(Event::SettingsChanged);
+        } else if let Some(i) = clicked(&self.rules).filter(|i| *i > 0) {
+            // The first item is the "Default rules:" label.
+            let rule = Rule::iterator()
+                .nth(i - 1)
+                .expect("Every rule has a toggle");
+            config.gameplay.rules.toggle(*rule);
+            events.publish(Event::SettingsChanged);
+        }
+    }
+
+    fn close(config: &Config, events: &mut EventBus<Event>) {
+        if let Err(e) = config.save() {
+            eprintln!("{e}");
+        }
+        events.publish(Event::Finished);
+    }
+
+    fn update_values(&mut self, config: &Config) {
+        for ((setting, _), value) in SETTINGS.iter().zip(&mut self.values) {
+            value.label = match setting {
+                Setting::MasterVolume => percent(config.audio.master),
+                Setting::MusicVolume => percent(config.audio.music),
+                Setting::SfxVolume => percent(config.audio.sfx),
+                Setting::MuteMusic => on_off(config.audio.mute_music),
+                Setting::MuteSfx => on_off(config.audio.mute_sfx),
+                Setting::Effects => match config.video.effects.as_slice() {
+                    [] => "None".into(),
+                    [effect] => effect.to_string(),
+                    _ => "Custom".into(),
+                },
+                Setting::EffectIntensity => percent(config.video.intensity),
+                Setting::AnimationSpeed => format!("{:.2}x", config.gameplay.animation_speed),
+                Setting::AiDelay => format!("{:.2}s", config.gameplay.ai_delay),
+                Setting::TooltipDelay => format!("{:.2}s", config.gameplay.tooltip_delay),
+                Setting::FadeDuration => format!("{:.1}s", config.gameplay.fade_duration),
+                Setting::Difficulty => format!("{}", config.gameplay.difficulty),
+                Setting::SuddenDeathRounds => match config.gameplay.max_sudden_death_rounds {
+                    0 => "No cap".into(),
+                    rounds => format!("{rounds}"),
+                },
+                Setting::FirstTurn => match config.gameplay.first_turn {
+                    FirstTurn::Random => "Random",
+                    FirstTurn::Loser => "Loser",
+                    FirstTurn::Player => "Player",
+                }
+                .into(),
+                Setting::ExplainCaptures => on_off(config.gameplay.explain_captures),
+            };
+        }
+        for (item, rule) in self.rules.iter_mut().skip(1).zip(Rule::iterator()) {
+            item.disabled = !config.gameplay.rules.enabled(*rule);
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        self.bg_rect.draw(canvas);
+        for item in self
+            .labels
+            .iter()
+            .chain(&self.values)
+            .chain(&self.decrease)
+            .chain(&self.increase)
+            .chain(&self.rules)
+        {
+            item.draw(canvas);
+        }
+        self.back.draw(canvas);
+    }
+}
+
+fn adjust(config: &mut Config, setting: Setting, direction: i8) {
+    let d = f32::from(direction);
+    match setting {
+        Setting::MasterVolume => {
+            config.audio.master = step(config.audio.master, d * 0.1, VOLUME_RANGE)
+        }
+        Setting::MusicVolume => {
+            config.audio.music = step(config.audio.music, d * 0.1, VOLUME_RANGE)
+        }
+        Setting::SfxVolume => config.audio.sfx = step(config.audio.sfx, d * 0.1, VOLUME_RANGE),
+        Setting::MuteMusic => config.audio.mute_music = !config.audio.mute_music,
+        Setting::MuteSfx => config.audio.mute_sfx = !config.audio.mute_sfx,
+        Setting::Effects => config.video.effects = next_effect(&config.video.effects, direction),
+        Setting::EffectIntensity => {
+            config.video.intensity = step(config.video.intensity, d * 0.1, INTENSITY_RANGE);
+        }
+        Setting::AnimationSpeed => {
+            config.gameplay.animation_speed = step(
+                config.gameplay.animation_speed,
+                d * 0.25,
+                ANIMATION_SPEED_RANGE,
+            );
+        }
+        Setting::AiDelay => {
+            config.gameplay.ai_delay = step(config.gameplay.ai_delay, d * 0.25, AI_DELAY_RANGE);
+        }
+        Setting::TooltipDelay => {
+            config.gameplay.tooltip_delay =
+                step(config.gameplay.tooltip_delay, d * 0.25, TOOLTIP_DELAY_RANGE);
+        }
+        Setting::FadeDuration => {
+            config.gameplay.fade_duration =
+                step(config.gameplay.fade_duration, d * 0.5, FADE_DURATION_RANGE);
+        }
+        Setting::Difficulty => {
+            config.gameplay.difficulty = match direction {
+                1 if config.gameplay.difficulty >= MAX_DIFFICULTY => 1,
+                1 => config.gameplay.difficulty + 1,
+                _ if config.gameplay.difficulty <= 1 => MAX_DIFFICULTY,
+                _ => config.gameplay.difficulty - 1,
+            };
+        }
+        Setting::SuddenDeathRounds => {
+            let rounds = &mut config.gameplay.max_sudden_death_rounds;
+            *rounds = match direction {
+                1 => (*rounds + 1).min(MAX_SUDDEN_DEATH_ROUNDS),
+                _ => rounds.saturating_sub(1),
+            };
+        }
+        Setting::FirstTurn => {
+            const CHOICES: [FirstTurn; 3] =
+                [FirstTurn::Random, FirstTurn::Loser, FirstTurn::Player];
+            let i = CHOICES
+                .iter()
+                .position(|choice| *choice == config.gameplay.first_turn)
+                .unwrap_or_default();
+            let next = match direction {
+                1 => (i + 1) % CHOICES.len(),
+                _ => (i + CHOICES.len() - 1) % CHOICES.len(),
+            };
+            config.gameplay.first_turn = CHOICES[next];
+        }
+        Setting::ExplainCaptures => {
+            config.gameplay.explain_captures = !config.gameplay.explain_captures;
+        }
+    }
+}
+
+/// Cycles through a single effect or none, a custom chain from the config
+/// file starts over at the first one.
+fn next_effect(effects: &[Effect], direction: i8) -> Vec<Effect> {
+    let presets = Effect::ALL.len() + 1;
+    let current = match effects {
+        [] => Some(Effect::ALL.len()),
+        [effect] => Effect::ALL.iter().position(|e| e == effect),
+        _ => None,
+    };
+    let next = match (current, direction) {
+        (None, _) => 0,
+        (Some(i), 1) => (i + 1) % presets,
+        (Some(i), _) => (i + presets - 1) % presets,
+    };
+    Effect::ALL
+        .get(next)
+        .map(|effect| vec![*effect])
+        .unwrap_or_default()
+}
+
+/// Moves `value` by `delta`, rounded to two decimals so repeated steps don't
+/// drift.
+fn step(value: f32, delta: f32, [min, max]: [f32; 2]) -> f32 {
+    ((value + delta) * 100.0)
+        .round()
+        .clamp(min * 100.0, max * 100.0)
+        / 100.0
+}
+
+fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+fn on_off(value: bool) -> String {
+    if value { "On" } else { "Off" }.into()
+}
@@ -0,0 +1,344 @@
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, Rect};
+use ggez::input::keyboard::KeyCode;
+use ggez::Context;
+
+use std::rc::Rc;
+
+use super::menu::MenuItem;
+use crate::consts::FONT_SIZE;
+use crate::core::{CardAtlas, DuelOutcome};
+use crate::events::EventBus;
+use crate::graphics::text::draw_label;
+use crate::graphics::TextBox;
+use crate::layout;
+use crate::profile::{Profile, Profiles, Record, PROFILE_NAME_MAX_LEN};
+use crate::utils::{Event, Sfx};
+
+const LINE_HEIGHT: f32 = FONT_SIZE + 5.0;
+const ROWS_PER_PAGE: usize = 14;
+const CARD_ROWS: usize = 13;
+
+const HEADER_Y: f32 = 15.0;
+const PANEL_POS: [f32; 2] = [20.0, 60.0];
+const PANEL_DIMENSIONS: [f32; 2] = [760.0, 470.0];
+const LEFT_X: f32 = 35.0;
+const RIGHT_X: f32 = 410.0;
+const FOOTER_Y: f32 = 555.0;
+
+const PREV_PROFILE: usize = 0;
+const NEXT_PROFILE: usize = 1;
+const NEW_PROFILE: usize = 2;
+const OVERVIEW_TAB: usize = 3;
+const CARDS_TAB: usize = 4;
+const HISTORY_TAB: usize = 5;
+const PREV_PAGE: usize = 6;
+const NEXT_PAGE: usize = 7;
+const BACK_BUTTON: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Overview,
+    Cards,
+    History,
+}
+
+/// Browses the player profiles: results, combos, cards and the latest
+/// matches. A new profile is named by typing, Enter creates it.
+pub struct Stats {
+    bg_rect: TextBox,
+    buttons: Vec<MenuItem>,
+    tab: Tab,
+    page: usize,
+    /// The name of the profile being created.
+    naming: Option<String>,
+    card_atlas: Rc<CardAtlas>,
+}
+
+impl Stats {
+    pub fn new(ctx: &mut Context, card_atlas: &Rc<CardAtlas>) -> Self {
+        let buttons = [
+            ("<", 20.0, HEADER_Y),
+            (">", 300.0, HEADER_Y),
+            ("New", 340.0, HEADER_Y),
+            ("Overview", 450.0, HEADER_Y),
+            ("Cards", 570.0, HEADER_Y),
+            ("History", 660.0, HEADER_Y),
+            ("<", 280.0, FOOTER_Y),
+            (">", 320.0, FOOTER_Y),
+            ("Back", 680.0, FOOTER_Y),
+        ]
+        .iter()
+        .map(|(label, x, y)| MenuItem {
+            label: String::from(*label),
+            disabled: false,
+            rect: Rect::new(
+                *x,
+                *y,
+                f32::from(u8::try_from(label.len()).expect("Value is too big")) * 12.0 + 8.0,
+                FONT_SIZE,
+            ),
+            callback: None,
+        })
+        .collect();
+
+        Self {
+            bg_rect: TextBox::new(ctx, PANEL_POS, PANEL_DIMENSIONS),
+            buttons,
+            tab: Tab::Overview,
+            page: 0,
+            naming: None,
+            card_atlas: Rc::clone(card_atlas),
+        }
+    }
+
+    pub fn init(&mut self) {
+        self.tab = Tab::Overview;
+        self.page = 0;
+        self.naming = None;
+    }
+
+    pub fn text_input(&mut self, character: char) {
+        if let Some(name) = &mut self.naming {
+            if !character.is_control() && name.chars().count() < PROFILE_NAME_MAX_LEN {
+                name.push(character);
+            }
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        profiles: &mut Profiles,
+        events: &mut EventBus<Event>,
+    ) {
+        if let Some(name) = &mut self.naming {
+            if ctx.keyboard.is_key_just_pressed(KeyCode::Back) {
+                name.pop();
+            }
+            if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+                self.naming = None;
+                events.publish(Event::PlaySound(Sfx::Cancel));
+            } else if ctx.keyboard.is_key_just_pressed(KeyCode::Return) {
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    events.publish(Event::PlaySound(Sfx::Cancel));
+                    return;
+                }
+                profiles.add(&name);
+                self.naming = None;
+                self.page = 0;
+                events.publish(Event::PlaySound(Sfx::Select));
+            }
+            return;
+        }
+
+        if ctx.keyboard.is_key_just_pressed(KeyCode::Escape) {
+            events.publish(Event::Finished);
+            return;
+        }
+        if !ctx.mouse.button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let mouse = layout::mouse_position(ctx);
+        let Some(button) = self
+            .buttons
+            .iter()
+            .position(|item| item.rect.contains(mouse))
+        else {
+            return;
+        };
+        let n = profiles.profiles.len();
+        let pages = self.pages(profiles.active());
+        match button {
+            PREV_PROFILE => profiles.select((profiles.active + n - 1) % n),
+            NEXT_PROFILE => profiles.select((profiles.active + 1) % n),
+            NEW_PROFILE => self.naming = Some(String::new()),
+            OVERVIEW_TAB => self.tab = Tab::Overview,
+            CARDS_TAB => self.tab = Tab::Cards,
+            HISTORY_TAB => self.tab = Tab::History,
+            PREV_PAGE if self.tab == Tab::History => {
+                self.page = (self.page + pages - 1) % pages;
+                events.publish(Event::PlaySound(Sfx::Move));
+                return;
+            }
+            NEXT_PAGE if self.tab == Tab::History => {
+                self.page = (self.page + 1) % pages;
+                events.publish(Event::PlaySound(Sfx::Move));
+                return;
+            }
+            BACK_BUTTON => {
+                events.publish(Event::Finished);
+                return;
+            }
+            _ => return,
+        }
+        self.page = 0;
+        events.publish(Event::PlaySound(Sfx::Select));
+    }
+
+    fn pages(&self, profile: &Profile) -> usize {
+        profile.history.len().div_ceil(ROWS_PER_PAGE).max(1)
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, profiles: &Profiles) {
+        let profile = profiles.active();
+        match &self.naming {
+            Some(name) => draw_label(canvas, &format!("New profile: {name}_"), [20.0, HEADER_Y]),
+            None => {
+                for (n, item) in self.buttons.iter().enumerate() {
+                    let tab = match n {
+                        OVERVIEW_TAB => Some(Tab::Overview),
+                        CARDS_TAB => Some(Tab::Cards),
+                        HISTORY_TAB => Some(Tab::History),
+                        PREV_PAGE | NEXT_PAGE if self.tab != Tab::History => continue,
+                        _ => None,
+                    };
+                    if tab.is_some_and(|tab| tab != self.tab) {
+                        MenuItem {
+                            label: item.label.clone(),
+                            disabled: true,
+                            rect: item.rect,
+                            callback: None,
+                        }
+                        .draw(canvas);
+                    } else {
+                        item.draw(canvas);
+                    }
+                }
+                draw_label(canvas, &profile.name, [50.0, HEADER_Y]);
+            }
+        }
+
+        self.bg_rect.draw(canvas);
+        match self.tab {
+            Tab::Overview => Self::draw_overview(canvas, profile),
+            Tab::Cards => self.draw_cards(canvas, profile),
+            Tab::History => self.draw_history(canvas, profile),
+        }
+    }
+
+    fn draw_overview(canvas: &mut Canvas, profile: &Profile) {
+        let mut lines = vec![
+            format!(
+                "Matches: {}  {}",
+                profile.record.matches(),
+                record(profile.record)
+            ),
+            format!(
+                "Same: {}  Plus: {}  Combo: {}  Sudden death rounds: {}",
+                profile.same, profile.plus, profile.combo, profile.sudden_death_rounds
+            ),
+            format!(
+                "Best chain: {}  Achievements: {}",
+                profile.best_chain,
+                profile.unlocked.len()
+            ),
+            String::new(),
+            "By difficulty".into(),
+        ];
+        lines.extend(
+            profile
+                .by_difficulty
+                .iter()
+                .map(|(difficulty, r)| format!("  Difficulty {difficulty}: {}", record(*r))),
+        );
+        lines.push(String::new());
+        lines.push("By rules".into());
+        let mut by_rules: Vec<_> = profile.by_rules.iter().collect();
+        by_rules.sort_by_key(|(_, r)| std::cmp::Reverse(r.matches()));
+        lines.extend(
+            by_rules
+                .iter()
+                .map(|(rules, r)| format!("  {rules}: {}", record(**r))),
+        );
+        for (n, line) in lines.iter().take(ROWS_PER_PAGE + 1).enumerate() {
+            draw_label(canvas, line, [LEFT_X, line_y(n)]);
+        }
+    }
+
+    fn draw_cards(&self, canvas: &mut Canvas, profile: &Profile) {
+        let name = |key: &str| {
+            self.card_atlas.position(key).map_or_else(
+                || key.to_string(),
+                |id| self.card_atlas.cards[id].name.clone(),
+            )
+        };
+
+        draw_label(canvas, "Most played", [LEFT_X, line_y(0)]);
+        for (n, (key, stats)) in profile.most_played().iter().take(CARD_ROWS).enumerate() {
+            draw_label(
+                canvas,
+                &format!("{} x{}", name(key), stats.played),
+                [LEFT_X, line_y(n + 1)],
+            );
+        }
+
+        draw_label(canvas, "Capture rate", [RIGHT_X, line_y(0)]);
+        for (n, (key, stats)) in profile.best_captured().iter().take(CARD_ROWS).enumerate() {
+            let rate = stats.capture_rate().unwrap_or_default() * 100.0;
+            draw_label(
+                canvas,
+                &format!(
+                    "{} {rate:.0}% ({}/{})",
+                    name(key),
+                    stats.captured,
+                    stats.faced
+                ),
+                [RIGHT_X, line_y(n + 1)],
+            );
+        }
+    }
+
+    fn draw_history(&self, canvas: &mut Canvas, profile: &Profile) {
+        if profile.history.is_empty() {
+            draw_label(canvas, "No matches yet", [LEFT_X, line_y(0)]);
+        }
+        for (n, entry) in profile
+            .history
+            .iter()
+            .skip(self.page * ROWS_PER_PAGE)
+            .take(ROWS_PER_PAGE)
+            .enumerate()
+        {
+            let outcome = match entry.outcome {
+                DuelOutcome::Win => "Win ",
+                DuelOutcome::Lose => "Loss",
+                DuelOutcome::Draw => "Draw",
+            };
+            let rounds = if entry.sudden_death_rounds > 0 {
+                format!(" SD{}", entry.sudden_death_rounds)
+            } else {
+                String::new()
+            };
+            draw_label(
+                canvas,
+                &format!(
+                    "{outcome} {}-{}{rounds} vs {} D{} {}",
+                    entry.score[0], entry.score[1], entry.opponent, entry.difficulty, entry.rules
+                ),
+                [LEFT_X, line_y(n)],
+            );
+        }
+        draw_label(
+            canvas,
+            &format!("{}/{}", self.page + 1, self.pages(profile)),
+            [360.0, FOOTER_Y],
+        );
+    }
+}
+
+fn record(record: Record) -> String {
+    format!(
+        "W {} / L {} / D {}",
+        record.wins, record.losses, record.draws
+    )
+}
+
+fn line_y(n: usize) -> f32 {
+    LINE_HEIGHT.mul_add(
+        f32::from(u8::try_from(n).expect("Value is too big")),
+        PANEL_POS[1] + 10.0,
+    )
+}
@@ -0,0 +1,53 @@
+use ggez::graphics::Canvas;
+use ggez::Context;
+
+use std::collections::VecDeque;
+
+use crate::consts::{self, FONT_SIZE};
+use crate::graphics::text::draw_label;
+use crate::graphics::TextBox;
+
+const DURATION: f32 = 3.0;
+const DIMENSIONS: [f32; 2] = [420.0, 70.0];
+const POS: [f32; 2] = [(consts::WINDOW_DIMENSIONS[0] - DIMENSIONS[0]) / 2.0, 10.0];
+
+/// Short notices at the top of the screen, shown one after the other.
+pub struct Toast {
+    bg_rect: TextBox,
+    queue: VecDeque<(String, String)>,
+    timer: f32,
+}
+
+impl Toast {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self {
+            bg_rect: TextBox::new(ctx, POS, DIMENSIONS),
+            queue: VecDeque::new(),
+            timer: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, title: &str, message: &str) {
+        self.queue.push_back((title.into(), message.into()));
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.queue.is_empty() {
+            return;
+        }
+        self.timer += dt;
+        if self.timer >= DURATION {
+            self.timer = 0.0;
+            self.queue.pop_front();
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let Some((title, message)) = self.queue.front() else {
+            return;
+        };
+        self.bg_rect.draw(canvas);
+        draw_label(canvas, title, [POS[0] + 15.0, POS[1] + 8.0]);
+        draw_label(canvas, message, [POS[0] + 15.0, POS[1] + 13.0 + FONT_SIZE]);
+    }
+}
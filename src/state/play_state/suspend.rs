@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+
+use super::state::MatchStats;
+use crate::core::{Element, Rules, Suit};
+use crate::error::AssetError;
+use crate::persist;
+
+pub const SUSPEND_FILE: &str = "suspended_duel.json";
+
+/// A card of the duel, by key so the file survives changes of the atlas order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedCard {
+    pub card: String,
+    pub controller: Suit,
+    /// Face down in the opponent's hand.
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// The board at the start of a turn.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedBoard {
+    pub rules: Rules,
+    /// The side about to play.
+    pub turn: Suit,
+    /// Hand slots, top to bottom.
+    pub red_hand: Vec<Option<SavedCard>>,
+    pub blue_hand: Vec<Option<SavedCard>>,
+    /// Board cells, row by row.
+    pub field: Vec<Option<SavedCard>>,
+    pub elements: Vec<Option<Element>>,
+    pub stats: MatchStats,
+    /// Sudden Death rounds dealt so far.
+    #[serde(default)]
+    pub round: u32,
+    /// Seed of the next random draw, so elements and coin flips come out the
+    /// same after resuming.
+    pub seed: u64,
+    pub difficulty: usize,
+    /// How long the AI waits before each move, `None` in files saved before
+    /// it was kept.
+    #[serde(default)]
+    pub think_delay: Option<f32>,
+}
+
+/// A duel left before it ended, offered to be continued on the next launch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SuspendedDuel {
+    pub board: SavedBoard,
+    /// Id of the campaign opponent, for a campaign match.
+    #[serde(default)]
+    pub campaign: Option<String>,
+    /// Id of the free play opponent, `None` for a random one.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// The rules and hands the duel started with, to restart it.
+    pub setup_rules: Rules,
+    pub setup_red: Vec<String>,
+    pub setup_blue: Vec<String>,
+}
+
+impl SuspendedDuel {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(SUSPEND_FILE)
+    }
+
+    /// The duel left on the last run, if any. A file that does not parse is
+    /// reported and ignored.
+    pub fn load(dir: &Path) -> Option<Self> {
+        persist::read(&Self::path(dir))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), AssetError> {
+        persist::write(&Self::path(dir), self)
+    }
+
+    /// Forgets the suspended duel, once it ended or was declined.
+    pub fn remove(dir: &Path) {
+        let path = Self::path(dir);
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                eprintln!("{SUSPEND_FILE}: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Debug;
+
+/// A change of the scene stack the owner reacts to, see
+/// `SceneStack::take_hooks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook<S> {
+    /// The scene was pushed.
+    Enter(S),
+    /// The scene was popped.
+    Exit(S),
+    /// Another scene was pushed on top of it.
+    Pause(S),
+    /// The scene on top of it was popped.
+    Resume(S),
+}
+
+/// A stack of screens, the last one is active. Every change is recorded as a
+/// hook so the owner can set screens up and tear them down in one place.
+#[derive(Debug)]
+pub struct SceneStack<S> {
+    scenes: Vec<S>,
+    hooks: Vec<Hook<S>>,
+}
+
+impl<S: Copy + Debug> SceneStack<S> {
+    pub fn new(scenes: &[S]) -> Self {
+        let mut stack = Self {
+            scenes: Vec::new(),
+            hooks: Vec::new(),
+        };
+        stack.push_all(scenes);
+        stack
+    }
+
+    pub fn push(&mut self, scene: S) {
+        if let Some(top) = self.scenes.last() {
+            self.hooks.push(Hook::Pause(*top));
+        }
+        self.scenes.push(scene);
+        self.hooks.push(Hook::Enter(scene));
+    }
+
+    /// Pushes the scenes in order, the last one ends up active.
+    pub fn push_all(&mut self, scenes: &[S]) {
+        for scene in scenes {
+            self.push(*scene);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<S> {
+        let scene = self.scenes.pop()?;
+        self.hooks.push(Hook::Exit(scene));
+        if let Some(top) = self.scenes.last() {
+            self.hooks.push(Hook::Resume(*top));
+        }
+        Some(scene)
+    }
+
+    /// Swaps the active scene for another, the one below stays paused.
+    pub fn replace(&mut self, scene: S) {
+        if let Some(old) = self.scenes.pop() {
+            self.hooks.push(Hook::Exit(old));
+        }
+        self.scenes.push(scene);
+        self.hooks.push(Hook::Enter(scene));
+    }
+
+    /// Exits every scene, the active one first.
+    pub fn clear(&mut self) {
+        while let Some(scene) = self.scenes.pop() {
+            self.hooks.push(Hook::Exit(scene));
+        }
+    }
+
+    /// Replaces the whole stack.
+    pub fn set(&mut self, scenes: &[S]) {
+        self.clear();
+        self.push_all(scenes);
+    }
+
+    pub fn last(&self) -> Option<&S> {
+        self.scenes.last()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, S> {
+        self.scenes.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// The hooks recorded since the last call, oldest first.
+    pub fn take_hooks(&mut self) -> Vec<Hook<S>> {
+        std::mem::take(&mut self.hooks)
+    }
+
+    /// One scene per line, the active one first.
+    pub fn describe(&self) -> String {
+        self.scenes
+            .iter()
+            .rev()
+            .map(|scene| format!("{scene:?}\n"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Scene {
+        Menu,
+        Play,
+        Help,
+        Settings,
+    }
+
+    use Hook::{Enter, Exit, Pause, Resume};
+    use Scene::{Help, Menu, Play, Settings};
+
+    #[test]
+    fn new_pushes_in_order() {
+        let mut stack = SceneStack::new(&[Menu, Play]);
+        assert_eq!(stack.take_hooks(), [Enter(Menu), Pause(Menu), Enter(Play)]);
+        assert_eq!(stack.last(), Some(&Play));
+        assert!(stack.take_hooks().is_empty());
+    }
+
+    #[test]
+    fn push_and_pop_pause_and_resume() {
+        let mut stack = SceneStack::new(&[Play]);
+        stack.take_hooks();
+        stack.push_all(&[Help, Settings]);
+        assert_eq!(
+            stack.take_hooks(),
+            [Pause(Play), Enter(Help), Pause(Help), Enter(Settings)]
+        );
+        assert_eq!(stack.pop(), Some(Settings));
+        assert_eq!(stack.take_hooks(), [Exit(Settings), Resume(Help)]);
+        assert_eq!(stack.pop(), Some(Help));
+        assert_eq!(stack.pop(), Some(Play));
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.take_hooks(), [Exit(Help), Resume(Play), Exit(Play)]);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn replace_keeps_the_scene_below_paused() {
+        let mut stack = SceneStack::new(&[Play, Menu]);
+        stack.take_hooks();
+        stack.replace(Settings);
+        assert_eq!(stack.take_hooks(), [Exit(Menu), Enter(Settings)]);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [Play, Settings]);
+
+        let mut stack = SceneStack::new(&[]);
+        stack.replace(Menu);
+        assert_eq!(stack.take_hooks(), [Enter(Menu)]);
+    }
+
+    #[test]
+    fn set_exits_everything_first() {
+        let mut stack = SceneStack::new(&[Menu, Settings]);
+        stack.take_hooks();
+        stack.set(&[Play, Help]);
+        assert_eq!(
+            stack.take_hooks(),
+            [
+                Exit(Settings),
+                Exit(Menu),
+                Enter(Play),
+                Pause(Play),
+                Enter(Help)
+            ]
+        );
+        assert_eq!(stack.describe(), "Help\nPlay\n");
+    }
+}
@@ -16,6 +16,18 @@ impl Rule {
         use Rule::{Elemental, Open, Plus, Random, Same, SuddenDeath, Wall};
         [Open, Elemental, Random, Same, Wall, Plus, SuddenDeath].iter()
     }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::Elemental => "Elemental",
+            Self::Random => "Random",
+            Self::Same => "Same",
+            Self::Wall => "Wall",
+            Self::Plus => "Plus",
+            Self::SuddenDeath => "Sudden Death",
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -27,19 +39,51 @@ pub enum Event {
     GameSummary(DuelOutcome, bool),
     ChangeRule(Rule),
     ChangeDifficulty,
+    ChangeOpponent,
     ToggleCards(usize),
+    EditDecks,
+    OpenAlbum,
+    OpenCampaign,
+    OpenStats,
+    PlayCampaign(usize),
+    EditSettings,
+    SettingsChanged,
+    Restart,
+    Concede,
+    ReturnToMenu,
+    ContinueDuel,
     PlaySound(Sfx),
-    None,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Sfx {
     Move,
     Select,
     Cancel,
-    Fanfare,
     Flip,
+    Hover,
+    Combo,
+    ElementalBonus,
+    ElementalMalus,
+    Defeat,
+}
+
+impl Sfx {
+    /// Name of the cue in the audio manifest.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Move => "move",
+            Self::Select => "select",
+            Self::Cancel => "cancel",
+            Self::Flip => "flip",
+            Self::Hover => "hover",
+            Self::Combo => "combo",
+            Self::ElementalBonus => "elemental_bonus",
+            Self::ElementalMalus => "elemental_malus",
+            Self::Defeat => "defeat",
+        }
+    }
 }
 
 pub const fn border_mask(n: usize) -> [Option<usize>; 4] {
@@ -100,7 +144,7 @@ pub fn check_same(this: [u8; 4], other: [Option<u8>; 4]) -> [bool; 4] {
     let same_count = this
         .iter()
         .zip(other.iter())
-        .filter(|(r1, maybe_r2)| maybe_r2.map_or(false, |r2| *r1 == &r2))
+        .filter(|(r1, maybe_r2)| maybe_r2.is_some_and(|r2| *r1 == &r2))
         .count();
 
     if same_count < 2 {
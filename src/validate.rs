@@ -0,0 +1,476 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use serde_json::Value;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::consts::{CARD_NAME_MAX_WIDTH, FONT_SIZE, RIGHT_ARROW_SPRITE_ID};
+use crate::core::Element;
+use crate::graphics::sprite::Atlas;
+
+const RANKS: [&str; 4] = ["powTop", "powRight", "powBottom", "powLeft"];
+const MIN_RANK: u64 = 1;
+const MAX_RANK: u64 = 10;
+
+#[derive(Debug)]
+pub enum Problem {
+    Malformed {
+        file: String,
+        reason: String,
+    },
+    MissingId {
+        index: usize,
+    },
+    DuplicateId {
+        id: u64,
+    },
+    IdGap {
+        id: u64,
+    },
+    IdNotIndex {
+        index: usize,
+        id: u64,
+    },
+    MissingField {
+        card: String,
+        field: &'static str,
+    },
+    RankOutOfRange {
+        card: String,
+        side: &'static str,
+        value: u64,
+    },
+    UnknownElement {
+        card: String,
+        element: String,
+    },
+    LevelOutOfRange {
+        card: String,
+        level: u64,
+    },
+    LevelWithoutCards {
+        level: u64,
+    },
+    SpriteOutOfRange {
+        sprite: String,
+        id: u64,
+        sprites: usize,
+    },
+    NameTooWide {
+        card: String,
+        width: f32,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { file, reason } => write!(f, "{file}: {reason}"),
+            Self::MissingId { index } => write!(f, "card #{index} has no `id`"),
+            Self::DuplicateId { id } => write!(f, "id {id} is used by more than one card"),
+            Self::IdGap { id } => write!(f, "id {id} is missing"),
+            Self::IdNotIndex { index, id } => {
+                write!(
+                    f,
+                    "card #{index} has id {id}, base cards must be in id order"
+                )
+            }
+            Self::MissingField { card, field } => write!(f, "{card}: `{field}` is missing"),
+            Self::RankOutOfRange { card, side, value } => write!(
+                f,
+                "{card}: `{side}` is {value}, expected {MIN_RANK}-{MAX_RANK}"
+            ),
+            Self::UnknownElement { card, element } => {
+                write!(f, "{card}: unknown element `{element}`")
+            }
+            Self::LevelOutOfRange { card, level } => {
+                write!(
+                    f,
+                    "{card}: `level` is {level}, expected at most {}",
+                    u8::MAX
+                )
+            }
+            Self::LevelWithoutCards { level } => write!(f, "level {level} has no cards"),
+            Self::SpriteOutOfRange {
+                sprite,
+                id,
+                sprites,
+            } => write!(
+                f,
+                "{sprite}: sprite {id} is out of range, the atlas has {sprites} sprites"
+            ),
+            Self::NameTooWide { card, width } => write!(
+                f,
+                "{card}: name is {width:.0}px wide, the picker fits {CARD_NAME_MAX_WIDTH:.0}px"
+            ),
+        }
+    }
+}
+
+/// Checks a `cards.json` against its sprite atlas.
+///
+/// Base atlases index cards and sprites by card id, so ids must follow the card
+/// order without gaps, and also have to carry the UI sprites. Packs look
+/// sprites up by their `id` field (see `mods::install`).
+pub fn validate(cards: &Value, sprites: &Value, font: &FontRef, pack: bool) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let Some(cards) = cards.get("cards").and_then(Value::as_array) else {
+        problems.push(Problem::Malformed {
+            file: "cards.json".into(),
+            reason: "`cards` array is missing".into(),
+        });
+        return problems;
+    };
+    let atlas = serde_json::from_value::<Atlas>(sprites.clone());
+    let Some(sprites) = sprites.get("sprites").and_then(Value::as_array) else {
+        problems.push(Problem::Malformed {
+            file: "card_sprites.json".into(),
+            reason: "`sprites` array is missing".into(),
+        });
+        return problems;
+    };
+    // The game reads the frames and the sheet size, the checks below only
+    // need the ids.
+    if let Err(e) = atlas {
+        problems.push(Problem::Malformed {
+            file: "card_sprites.json".into(),
+            reason: e.to_string(),
+        });
+    }
+    let sprite_ids: Vec<Option<u64>> = sprites
+        .iter()
+        .map(|sprite| sprite.get("id").and_then(Value::as_u64))
+        .collect();
+
+    let mut ids: HashMap<u64, usize> = HashMap::new();
+    let mut levels: BTreeSet<u64> = BTreeSet::new();
+
+    for (index, card) in cards.iter().enumerate() {
+        let name = card.get("name").and_then(Value::as_str);
+        let id = card.get("id").and_then(Value::as_u64);
+        let label = match (id, name) {
+            (Some(id), Some(name)) => format!("card {id} ({name})"),
+            (Some(id), None) => format!("card {id}"),
+            (None, _) => format!("card #{index}"),
+        };
+
+        match id {
+            Some(id) => {
+                *ids.entry(id).or_default() += 1;
+                if !pack && usize::try_from(id) != Ok(index) {
+                    problems.push(Problem::IdNotIndex { index, id });
+                }
+            }
+            None => problems.push(Problem::MissingId { index }),
+        }
+
+        for side in RANKS {
+            match card.get(side).and_then(Value::as_u64) {
+                Some(value) if (MIN_RANK..=MAX_RANK).contains(&value) => {}
+                Some(value) => problems.push(Problem::RankOutOfRange {
+                    card: label.clone(),
+                    side,
+                    value,
+                }),
+                None => problems.push(Problem::MissingField {
+                    card: label.clone(),
+                    field: side,
+                }),
+            }
+        }
+
+        match card.get("element") {
+            None | Some(Value::Null) => {}
+            Some(element) => {
+                if serde_json::from_value::<Element>(element.clone()).is_err() {
+                    problems.push(Problem::UnknownElement {
+                        card: label.clone(),
+                        element: element
+                            .as_str()
+                            .map_or_else(|| element.to_string(), Into::into),
+                    });
+                }
+            }
+        }
+
+        match card.get("level").and_then(Value::as_u64) {
+            Some(level) if level > u64::from(u8::MAX) => {
+                problems.push(Problem::LevelOutOfRange {
+                    card: label.clone(),
+                    level,
+                });
+            }
+            Some(level) => {
+                levels.insert(level);
+            }
+            None => problems.push(Problem::MissingField {
+                card: label.clone(),
+                field: "level",
+            }),
+        }
+
+        if let Some(id) = id {
+            let in_range = if pack {
+                sprite_ids.contains(&Some(id))
+            } else {
+                usize::try_from(id).is_ok_and(|id| id < sprites.len())
+            };
+            if !in_range {
+                problems.push(Problem::SpriteOutOfRange {
+                    sprite: label.clone(),
+                    id,
+                    sprites: sprites.len(),
+                });
+            }
+        }
+
+        if let Some(name) = name {
+            let width = text_width(font, name);
+            if width > CARD_NAME_MAX_WIDTH {
+                problems.push(Problem::NameTooWide { card: label, width });
+            }
+        } else {
+            problems.push(Problem::MissingField {
+                card: label,
+                field: "name",
+            });
+        }
+    }
+
+    let mut duplicates: Vec<u64> = ids
+        .iter()
+        .filter(|(_, n)| **n > 1)
+        .map(|(id, _)| *id)
+        .collect();
+    duplicates.sort_unstable();
+    problems.extend(duplicates.into_iter().map(|id| Problem::DuplicateId { id }));
+
+    if let Some(max_id) = ids.keys().max().filter(|_| !pack) {
+        problems.extend(
+            (0..*max_id)
+                .filter(|id| !ids.contains_key(id))
+                .map(|id| Problem::IdGap { id }),
+        );
+    }
+
+    if let Some(max_level) = levels.last() {
+        problems.extend(
+            (1..*max_level)
+                .filter(|level| !levels.contains(level))
+                .map(|level| Problem::LevelWithoutCards { level }),
+        );
+    }
+
+    if !pack {
+        let id = u64::try_from(RIGHT_ARROW_SPRITE_ID).expect("Value is too big");
+        if RIGHT_ARROW_SPRITE_ID >= sprites.len() {
+            problems.push(Problem::SpriteOutOfRange {
+                sprite: "UI".into(),
+                id,
+                sprites: sprites.len(),
+            });
+        }
+    }
+
+    problems
+}
+
+/// Width of `text` rendered with the picker font size.
+pub fn text_width(font: &FontRef, text: &str) -> f32 {
+    let font = font.as_scaled(PxScale::from(FONT_SIZE));
+    let mut width = 0.0;
+    let mut prev = None;
+    for c in text.chars() {
+        let glyph = font.glyph_id(c);
+        if let Some(prev) = prev {
+            width += font.kern(prev, glyph);
+        }
+        width += font.h_advance(glyph);
+        prev = Some(glyph);
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FONT: &[u8] = include_bytes!("../assets/seed-computer.ttf");
+
+    fn card(id: u64, name: &str) -> Value {
+        json!({
+            "id": id, "name": name, "level": 1,
+            "powTop": 1, "powRight": 2, "powBottom": 3, "powLeft": 4
+        })
+    }
+
+    fn sprites(count: usize) -> Value {
+        let sprites: Vec<Value> = (0..count)
+            .map(|id| json!({ "id": id, "frame": { "x": 0, "y": 0, "w": 64, "h": 64 } }))
+            .collect();
+        json!({ "sprites": sprites, "meta": { "size": { "w": 64, "h": 64 } } })
+    }
+
+    fn check(cards: Vec<Value>, sprites: &Value, pack: bool) -> Vec<Problem> {
+        let font = FontRef::try_from_slice(FONT).unwrap();
+        validate(&json!({ "cards": cards }), sprites, &font, pack)
+    }
+
+    fn base(cards: Vec<Value>) -> Vec<Problem> {
+        check(cards, &sprites(RIGHT_ARROW_SPRITE_ID + 1), false)
+    }
+
+    #[test]
+    fn valid_atlas() {
+        assert!(base(vec![card(0, "Geezard"), card(1, "Funguar")]).is_empty());
+        assert!(check(vec![card(7, "Geezard")], &sprites(8), true).is_empty());
+    }
+
+    #[test]
+    fn malformed() {
+        let font = FontRef::try_from_slice(FONT).unwrap();
+        let problems = validate(&json!({}), &sprites(1), &font, false);
+        assert!(matches!(problems[..], [Problem::Malformed { .. }]));
+        let problems = validate(&json!({ "cards": [] }), &json!({}), &font, false);
+        assert!(matches!(problems[..], [Problem::Malformed { .. }]));
+    }
+
+    #[test]
+    fn missing_id() {
+        let mut geezard = card(0, "Geezard");
+        geezard.as_object_mut().unwrap().remove("id");
+        let problems = base(vec![geezard]);
+        assert!(matches!(problems[..], [Problem::MissingId { index: 0 }]));
+    }
+
+    #[test]
+    fn duplicate_id() {
+        let problems = check(
+            vec![card(0, "Geezard"), card(0, "Funguar")],
+            &sprites(1),
+            true,
+        );
+        assert!(matches!(problems[..], [Problem::DuplicateId { id: 0 }]));
+    }
+
+    #[test]
+    fn id_gap_only_in_base() {
+        let cards = || vec![card(0, "Geezard"), card(2, "Funguar")];
+        let problems = base(cards());
+        assert!(problems
+            .iter()
+            .any(|problem| matches!(problem, Problem::IdGap { id: 1 })));
+        assert!(check(cards(), &sprites(3), true).is_empty());
+    }
+
+    #[test]
+    fn id_not_index() {
+        let problems = base(vec![card(1, "Geezard"), card(0, "Funguar")]);
+        assert!(matches!(
+            problems[..],
+            [
+                Problem::IdNotIndex { index: 0, id: 1 },
+                Problem::IdNotIndex { index: 1, id: 0 }
+            ]
+        ));
+    }
+
+    #[test]
+    fn missing_field() {
+        let mut geezard = card(0, "Geezard");
+        geezard.as_object_mut().unwrap().remove("powLeft");
+        let problems = base(vec![geezard]);
+        assert!(matches!(
+            problems[..],
+            [Problem::MissingField {
+                field: "powLeft",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn rank_out_of_range() {
+        let mut geezard = card(0, "Geezard");
+        geezard["powTop"] = json!(11);
+        let problems = base(vec![geezard]);
+        assert!(matches!(
+            problems[..],
+            [Problem::RankOutOfRange {
+                side: "powTop",
+                value: 11,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn unknown_element() {
+        let mut geezard = card(0, "Geezard");
+        geezard["element"] = json!("Shadow");
+        let problems = base(vec![geezard]);
+        assert!(
+            matches!(&problems[..], [Problem::UnknownElement { element, .. }] if element == "Shadow")
+        );
+    }
+
+    #[test]
+    fn level_without_cards() {
+        let mut funguar = card(1, "Funguar");
+        funguar["level"] = json!(3);
+        let problems = base(vec![card(0, "Geezard"), funguar]);
+        assert!(matches!(
+            problems[..],
+            [Problem::LevelWithoutCards { level: 2 }]
+        ));
+    }
+
+    #[test]
+    fn level_out_of_range() {
+        let mut funguar = card(1, "Funguar");
+        funguar["level"] = json!(4_000_000_000_u64);
+        let problems = base(vec![card(0, "Geezard"), funguar]);
+        assert!(matches!(
+            problems[..],
+            [Problem::LevelOutOfRange {
+                level: 4_000_000_000,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn malformed_sprite_frames() {
+        let mut sheet = sprites(RIGHT_ARROW_SPRITE_ID + 1);
+        sheet["sprites"][0]["frame"]["x"] = json!("left");
+        let problems = check(vec![card(0, "Geezard")], &sheet, false);
+        assert!(matches!(problems[..], [Problem::Malformed { .. }]));
+
+        let mut sheet = sprites(RIGHT_ARROW_SPRITE_ID + 1);
+        sheet.as_object_mut().unwrap().remove("meta");
+        let problems = check(vec![card(0, "Geezard")], &sheet, false);
+        assert!(matches!(problems[..], [Problem::Malformed { .. }]));
+    }
+
+    #[test]
+    fn sprite_out_of_range() {
+        let problems = check(vec![card(3, "Geezard")], &sprites(3), true);
+        assert!(matches!(
+            problems[..],
+            [Problem::SpriteOutOfRange { id: 3, .. }]
+        ));
+        let problems = check(vec![card(0, "Geezard")], &sprites(1), false);
+        assert!(matches!(
+            problems[..],
+            [Problem::SpriteOutOfRange { ref sprite, .. }] if sprite == "UI"
+        ));
+    }
+
+    #[test]
+    fn name_too_wide() {
+        let problems = base(vec![card(0, &"W".repeat(40))]);
+        assert!(matches!(problems[..], [Problem::NameTooWide { .. }]));
+    }
+}